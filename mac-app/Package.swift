@@ -9,15 +9,27 @@ let package = Package(
     products: [
         .executable(name: "mac-app", targets: ["mac-app"]) 
     ],
+    dependencies: [
+        .package(url: "https://github.com/jpsim/Yams.git", from: "5.0.0"),
+        .package(url: "https://github.com/LebJe/TOMLKit.git", from: "0.5.0")
+    ],
     targets: [
         .executableTarget(
             name: "mac-app",
-            dependencies: [],
+            dependencies: [
+                .product(name: "Yams", package: "Yams"),
+                .product(name: "TOMLKit", package: "TOMLKit")
+            ],
             path: "Sources",
             resources: [
                 .process("Resources")
             ],
             linkerSettings: []
+        ),
+        .testTarget(
+            name: "mac-appTests",
+            dependencies: ["mac-app"],
+            path: "Tests"
         )
     ]
 )
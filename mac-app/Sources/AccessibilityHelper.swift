@@ -0,0 +1,170 @@
+import Cocoa
+import Foundation
+import ApplicationServices
+
+/// Accessibility API（AXUIElement）の属性読み取りヘルパー
+/// - 取得失敗時は nil を返し、呼び出し側で CGWindowList の情報にフォールバックする
+enum AccessibilityHelper {
+
+    // 公開ヘッダに定数が無い属性
+    static let fullScreenAttribute = "AXFullScreen"
+    static let zoomedAttribute = "AXZoomed"
+    static let modalAttribute = "AXModal"
+
+    /// AXWindows から読み取ったウィンドウ1件分の情報
+    struct WindowSnapshot {
+        let element: AXUIElement
+        let title: String?
+        let identifier: String?
+        let role: String?
+        let subrole: String?
+        let frame: CGRect
+        let isMinimized: Bool
+        let isFullscreen: Bool
+        let isZoomed: Bool
+        /// ネイティブタブのタイトル（表示順。タブグループが無い場合は nil）
+        let tabTitles: [String]?
+        /// 選択中タブの位置
+        let selectedTabIndex: Int?
+    }
+
+    // MARK: - 属性取得
+
+    static func copyAttribute(_ element: AXUIElement, _ attribute: String) -> CFTypeRef? {
+        var value: CFTypeRef?
+        let err = AXUIElementCopyAttributeValue(element, attribute as CFString, &value)
+        guard err == .success else { return nil }
+        return value
+    }
+
+    static func string(_ element: AXUIElement, _ attribute: String) -> String? {
+        return copyAttribute(element, attribute) as? String
+    }
+
+    static func bool(_ element: AXUIElement, _ attribute: String) -> Bool? {
+        return (copyAttribute(element, attribute) as? NSNumber)?.boolValue
+    }
+
+    static func position(of element: AXUIElement) -> CGPoint? {
+        guard let value = copyAttribute(element, kAXPositionAttribute),
+              CFGetTypeID(value) == AXValueGetTypeID() else { return nil }
+        var point = CGPoint.zero
+        guard AXValueGetValue(value as! AXValue, .cgPoint, &point) else { return nil }
+        return point
+    }
+
+    static func size(of element: AXUIElement) -> CGSize? {
+        guard let value = copyAttribute(element, kAXSizeAttribute),
+              CFGetTypeID(value) == AXValueGetTypeID() else { return nil }
+        var size = CGSize.zero
+        guard AXValueGetValue(value as! AXValue, .cgSize, &size) else { return nil }
+        return size
+    }
+
+    static func frame(of element: AXUIElement) -> CGRect? {
+        guard let origin = position(of: element), let size = size(of: element) else { return nil }
+        return CGRect(origin: origin, size: size)
+    }
+
+    // MARK: - ロール判定
+
+    // 位置を復元すべきでないロール（シート/ドロワー/ポップオーバー）
+    private static let nonRepositionableRoles: Set<String> = [kAXSheetRole, kAXDrawerRole, "AXPopover"]
+
+    // 位置を復元すべきでないサブロール（ダイアログ類）
+    private static let nonRepositionableSubroles: Set<String> = [kAXDialogSubrole, kAXSystemDialogSubrole]
+
+    /// 通常ウィンドウとして位置を復元してよいロール/サブロールか（不明な場合は true）
+    static func isRepositionable(role: String?, subrole: String?) -> Bool {
+        if let role = role, nonRepositionableRoles.contains(role) { return false }
+        if let subrole = subrole, nonRepositionableSubroles.contains(subrole) { return false }
+        return true
+    }
+
+    /// ウィンドウがシートを表示中、またはアプリがモーダルウィンドウを表示中で操作できない状態か
+    static func isBlockedByDialog(_ window: AXUIElement, pid: pid_t) -> Bool {
+        if let children = copyAttribute(window, kAXChildrenAttribute) as? [AXUIElement],
+           children.contains(where: { string($0, kAXRoleAttribute) == kAXSheetRole }) {
+            return true
+        }
+        let appRef = AXUIElementCreateApplication(pid)
+        guard let windows = copyAttribute(appRef, kAXWindowsAttribute) as? [AXUIElement] else { return false }
+        return windows.contains { $0 != window && bool($0, modalAttribute) == true }
+    }
+
+    // MARK: - 属性設定
+
+    @discardableResult
+    static func setBool(_ element: AXUIElement, _ attribute: String, _ value: Bool) -> Bool {
+        let err = AXUIElementSetAttributeValue(element, attribute as CFString, (value ? kCFBooleanTrue : kCFBooleanFalse) as CFTypeRef)
+        return err == .success
+    }
+
+    static func setPosition(_ element: AXUIElement, _ point: CGPoint) -> AXError {
+        var point = point
+        guard let value = AXValueCreate(.cgPoint, &point) else { return .failure }
+        return AXUIElementSetAttributeValue(element, kAXPositionAttribute as CFString, value)
+    }
+
+    static func setSize(_ element: AXUIElement, _ size: CGSize) -> AXError {
+        var size = size
+        guard let value = AXValueCreate(.cgSize, &size) else { return .failure }
+        return AXUIElementSetAttributeValue(element, kAXSizeAttribute as CFString, value)
+    }
+
+    /// 位置とサイズを設定（CG グローバル座標）
+    /// 戻り値: 位置・サイズとも設定できた場合 true
+    @discardableResult
+    static func setFrame(_ element: AXUIElement, _ frame: CGRect) -> Bool {
+        let posErr = setPosition(element, frame.origin)
+        if posErr != .success { Log.warning("[restore] 位置設定失敗: \(posErr)") }
+        let sizeErr = setSize(element, frame.size)
+        if sizeErr != .success { Log.warning("[restore] サイズ設定失敗: \(sizeErr)") }
+        return posErr == .success && sizeErr == .success
+    }
+
+    // MARK: - ウィンドウ列挙
+
+    /// 指定プロセスの AXWindows を列挙
+    /// 戻り値: AX が利用できない（権限なし/非対応アプリ）場合は nil
+    static func windowSnapshots(pid: pid_t) -> [WindowSnapshot]? {
+        let appRef = AXUIElementCreateApplication(pid)
+        guard let elements = copyAttribute(appRef, kAXWindowsAttribute) as? [AXUIElement] else {
+            return nil
+        }
+        return elements.compactMap { element in
+            guard let frame = frame(of: element) else { return nil }
+            let tabs = tabGroupInfo(of: element)
+            return WindowSnapshot(element: element,
+                                  title: string(element, kAXTitleAttribute),
+                                  identifier: string(element, kAXIdentifierAttribute),
+                                  role: string(element, kAXRoleAttribute),
+                                  subrole: string(element, kAXSubroleAttribute),
+                                  frame: frame,
+                                  isMinimized: bool(element, kAXMinimizedAttribute) ?? false,
+                                  isFullscreen: bool(element, fullScreenAttribute) ?? false,
+                                  isZoomed: bool(element, zoomedAttribute) ?? false,
+                                  tabTitles: tabs?.titles,
+                                  selectedTabIndex: tabs?.selectedIndex)
+        }
+    }
+
+    // MARK: - タブグループ
+
+    /// ウィンドウ直下の AXTabGroup からタブのタイトルと選択位置を取得
+    static func tabGroupInfo(of window: AXUIElement) -> (titles: [String], selectedIndex: Int?)? {
+        guard let children = copyAttribute(window, kAXChildrenAttribute) as? [AXUIElement],
+              let tabGroup = children.first(where: { string($0, kAXRoleAttribute) == kAXTabGroupRole }) else {
+            return nil
+        }
+        // AXTabs が無い実装では子要素のラジオボタンをタブとして扱う
+        let tabs = (copyAttribute(tabGroup, kAXTabsAttribute) as? [AXUIElement])
+            ?? (copyAttribute(tabGroup, kAXChildrenAttribute) as? [AXUIElement])?
+                .filter { string($0, kAXRoleAttribute) == kAXRadioButtonRole }
+            ?? []
+        guard !tabs.isEmpty else { return nil }
+        let titles = tabs.map { string($0, kAXTitleAttribute) ?? "" }
+        let selectedIndex = tabs.firstIndex { (copyAttribute($0, kAXValueAttribute) as? NSNumber)?.intValue == 1 }
+        return (titles, selectedIndex)
+    }
+}
@@ -4,7 +4,6 @@
 
 import Cocoa
 import Foundation
-import UserNotifications
 
 /// メインアプリケーションデリゲート
 /// アプリケーションのライフサイクルとメニューバー常駐機能を管理
@@ -27,6 +26,12 @@ class AppDelegate: NSObject, NSApplicationDelegate {
     /// 設定ウィンドウ
     private var settingsWindow: SettingsWindow?
     
+    /// 設定ファイルの変更監視
+    private var configWatcher: ConfigWatcher?
+    
+    /// ディスプレイ接続時の自動復元
+    private var displayAutoRestorer: DisplayAutoRestorer?
+    
     
     /// アプリケーションの設定
     private var appSettings: AppSettings
@@ -47,7 +52,7 @@ class AppDelegate: NSObject, NSApplicationDelegate {
         print("Window Restore アプリケーションが起動しました")
         
         // 通知許可のリクエスト（初回のみ）
-        requestUserNotificationPermission()
+        NotificationManager.requestPermission()
 
         // Rustライブラリの初期化
         initializeRustLibrary()
@@ -64,9 +69,19 @@ class AppDelegate: NSObject, NSApplicationDelegate {
         // レイアウトセレクターの初期化
         setupLayoutSelector()
         
+        // 設定ファイルの変更監視
+        setupConfigWatcher()
+        
+        // ディスプレイ接続時の自動復元
+        setupDisplayAutoRestorer()
+        
         // 権限チェック
         checkInitialPermissions()
         
+        // 実行中の権限取り消しで常駐処理を止める
+        NotificationCenter.default.addObserver(self, selector: #selector(permissionRevoked),
+                                               name: WindowManager.permissionRevokedNotification, object: nil)
+        
         // アプリケーションをバックグラウンドで実行
         NSApp.setActivationPolicy(.accessory)
         
@@ -260,6 +275,37 @@ class AppDelegate: NSObject, NSApplicationDelegate {
         print("レイアウトセレクターの設定が完了しました")
     }
     
+    /// 設定ファイルの監視
+    /// 手動で編集された設定を再起動せずに反映する（保存先の変更に備えてレイアウト一覧も更新）
+    private func setupConfigWatcher() {
+        configWatcher = WindowManager.shared.watchConfig { [weak self] _ in
+            self?.menuController?.updateLayoutList(force: true)
+            self?.setupDisplayAutoRestorer()
+        }
+    }
+    
+    /// ディスプレイ接続時の自動復元の設定
+    /// 設定（auto_restore_on_display_change）に合わせて監視を開始/停止する
+    private func setupDisplayAutoRestorer() {
+        let config = Config.load()
+        if config.autoRestoreOnDisplayChange && config.displayChangeDetection {
+            if displayAutoRestorer == nil {
+                displayAutoRestorer = DisplayAutoRestorer()
+                displayAutoRestorer?.start()
+            }
+        } else {
+            displayAutoRestorer?.stop()
+            displayAutoRestorer = nil
+        }
+    }
+    
+    /// 実行中に権限が取り消された
+    /// ディスプレイ接続時の自動復元を停止（権限が戻ったら permissionStatusChanged で再開）
+    @objc private func permissionRevoked() {
+        displayAutoRestorer?.stop()
+        displayAutoRestorer = nil
+    }
+    
     /// 初期権限チェック
     /// アプリケーション起動時の権限状態を確認
     private func checkInitialPermissions() {
@@ -286,13 +332,13 @@ class AppDelegate: NSObject, NSApplicationDelegate {
     /// エラー通知の表示
     /// 引数: title - 通知タイトル、message - 通知メッセージ
     private func showErrorNotification(title: String, message: String) {
-        postUserNotification(title: title, body: message)
+        NotificationManager.post(title: title, body: message)
     }
     
     /// 権限要求通知の表示
     /// アクセシビリティ権限が必要であることをユーザーに通知
     private func showPermissionRequiredNotification() {
-        postUserNotification(title: "アクセシビリティ権限が必要です", body: "システム設定で有効にしてください。")
+        NotificationManager.post(title: "アクセシビリティ権限が必要です", body: "システム設定で有効にしてください。")
     }
 }
 
@@ -334,9 +380,7 @@ extension AppDelegate: MenuControllerDelegate {
                     let d = dup.runModal()
                     if d == .alertFirstButtonReturn {
                         // 置き換え
-                        let captured = WindowManager.shared.fetchVisibleAppWindows().map { w in
-                            WindowInfo(ownerName: w.ownerName, pid: w.pid, windowName: w.windowName, bounds: w.bounds, displayUUID: w.displayUUID, spaceNumber: w.spaceNumber, layoutLabel: baseLabel)
-                        }
+                        let captured = WindowManager.shared.fetchVisibleAppWindows().map { $0.withLabel(baseLabel) }
                         do {
                             try WindowManager.shared.replaceWindowsForLabel(name: name, label: baseLabel, with: captured)
                             usedLabel = baseLabel
@@ -443,18 +487,28 @@ extension AppDelegate: MenuControllerDelegate {
             }
         }
 
-        // 通常の一括復元
-        let result = RustAPI.restoreLayout(name: name)
+        // 通常の一括復元（ウィンドウが多い場合は進捗を通知し、完了時に結果へ置き換える）
+        let notifier = RestoreProgressNotifier(layoutName: name)
+        let result = RustAPI.restoreLayoutWithProgress(name: name, progress: notifier.handler)
         switch result {
         case .success:
             print("レイアウトの復元が成功しました: \(name)")
-            showSuccessNotification(title: "復元完了", message: "レイアウト「\(name)」が復元されました")
+            notifier.finish()
         case .failure(_, let message):
             print("レイアウトの復元に失敗しました: \(message)")
-            showErrorNotification(title: "復元エラー", message: message)
+            notifier.finish(errorMessage: message)
         }
     }
     
+    /// 設定の既定のレイアウトを復元
+    func restoreDefaultLayout() {
+        guard let name = WindowManager.shared.defaultLayoutName() else {
+            showErrorNotification(title: "復元エラー", message: "既定のレイアウトが設定されていないか、見つかりません（設定の default_layout）")
+            return
+        }
+        restoreLayout(name: name)
+    }
+    
     /// レイアウトを削除
     /// 引数: name - 削除するレイアウト名
     func deleteLayout(name: String) {
@@ -498,13 +552,13 @@ extension AppDelegate: MenuControllerDelegate {
     /// 成功通知の表示
     /// 引数: title - 通知タイトル、message - 通知メッセージ
     private func showSuccessNotification(title: String, message: String) {
-        postUserNotification(title: title, body: message)
+        NotificationManager.post(title: title, body: message)
     }
     
     /// 情報通知の表示
     /// 引数: title - 通知タイトル、message - 通知メッセージ
     private func showInfoNotification(title: String, message: String) {
-        postUserNotification(title: title, body: message)
+        NotificationManager.post(title: title, body: message)
     }
 }
 
@@ -521,8 +575,11 @@ extension AppDelegate: PermissionManagerDelegate {
         
         if hasPermission {
             showSuccessNotification(title: "権限取得", message: "アクセシビリティ権限が取得されました")
+            WindowManager.shared.resumeAfterPermissionGranted()
+            setupDisplayAutoRestorer()
         } else {
-            showPermissionRequiredNotification()
+            // 通知と常駐処理の一時停止は WindowManager 側で行う
+            WindowManager.shared.handlePermissionRevoked()
         }
     }
 }
@@ -577,27 +634,3 @@ struct AppSettings {
 }
 
 // rustLastError は FFIHelpers.swift を使用
-
-// MARK: - UserNotifications 簡易通知
-
-private func requestUserNotificationPermission() {
-    UNUserNotificationCenter.current().requestAuthorization(options: [.alert, .sound]) { granted, error in
-        if let error = error {
-            print("Notification permission error: \(error)")
-        } else {
-            print("Notification permission granted: \(granted)")
-        }
-    }
-}
-
-private func postUserNotification(title: String, body: String) {
-    let content = UNMutableNotificationContent()
-    content.title = title
-    content.body = body
-    let request = UNNotificationRequest(identifier: UUID().uuidString, content: content, trigger: nil)
-    UNUserNotificationCenter.current().add(request) { error in
-        if let error = error {
-            print("Failed to post notification: \(error)")
-        }
-    }
-}
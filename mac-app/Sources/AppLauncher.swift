@@ -0,0 +1,109 @@
+import Cocoa
+import Foundation
+
+/// レイアウト復元時のアプリ特定/起動
+/// - 保存時の bundle id から起動中プロセスを解決し、未起動なら起動して待機する
+enum AppLauncher {
+
+    // 起動待機のタイムアウト（秒）
+    static let defaultLaunchTimeout: TimeInterval = 10.0
+
+    // 起動状態のポーリング間隔（秒）
+    private static let pollInterval: TimeInterval = 0.2
+
+    /// PID から bundle id を解決（解決できないプロセスは nil）
+    static func bundleIdentifier(for pid: pid_t) -> String? {
+        return NSRunningApplication(processIdentifier: pid)?.bundleIdentifier
+    }
+
+    /// メニューバー常駐/エージェント（LSUIElement・LSBackgroundOnly）アプリか
+    /// - 起動中は activationPolicy、それ以外は Info.plist で判定
+    static func isAgentApplication(pid: pid_t) -> Bool {
+        guard let app = NSRunningApplication(processIdentifier: pid) else { return false }
+        if app.activationPolicy != .regular { return true }
+        return app.bundleURL.map { isAgentBundle(at: $0) } ?? false
+    }
+
+    /// bundle id のアプリがエージェントアプリか（インストール済みの Info.plist で判定）
+    static func isAgentApplication(bundleId: String) -> Bool {
+        guard let url = NSWorkspace.shared.urlForApplication(withBundleIdentifier: bundleId) else { return false }
+        return isAgentBundle(at: url)
+    }
+
+    private static func isAgentBundle(at url: URL) -> Bool {
+        guard let bundle = Bundle(url: url) else { return false }
+        for key in ["LSUIElement", "LSBackgroundOnly"] {
+            let value = bundle.object(forInfoDictionaryKey: key)
+            if let flag = value as? Bool, flag { return true }
+            if let text = value as? String, text == "1" || text.lowercased() == "true" { return true }
+        }
+        return false
+    }
+
+    /// bundle id のアプリがインストールされているか
+    static func isInstalled(bundleId: String) -> Bool {
+        return NSWorkspace.shared.urlForApplication(withBundleIdentifier: bundleId) != nil
+    }
+
+    /// 起動中のアプリを bundle id で検索
+    static func runningApplication(bundleId: String) -> NSRunningApplication? {
+        return NSRunningApplication.runningApplications(withBundleIdentifier: bundleId)
+            .first { !$0.isTerminated }
+    }
+
+    /// 複数アプリを必要に応じて起動し、起動完了まで待機
+    /// 戻り値: bundle id → PID（起動できなかったアプリは含まない）
+    static func launchAll(bundleIds: Set<String>, timeout: TimeInterval = defaultLaunchTimeout) -> [String: pid_t] {
+        var pending: Set<String> = []
+        for bundleId in bundleIds where runningApplication(bundleId: bundleId) == nil {
+            // エージェントアプリは起動してもウィンドウを開かないため起動しない（起動中なら位置のみ復元）
+            if isAgentApplication(bundleId: bundleId) {
+                Log.debug("[launch] エージェントアプリのため起動をスキップ: \(bundleId)")
+                continue
+            }
+            if launch(bundleId: bundleId) {
+                pending.insert(bundleId)
+            }
+        }
+
+        // 起動要求したアプリの起動完了を待機（メインRunLoopを回して状態更新を受け取る）
+        let deadline = Date().addingTimeInterval(timeout)
+        while !pending.isEmpty && Date() < deadline {
+            RunLoop.current.run(until: Date().addingTimeInterval(pollInterval))
+            pending = pending.filter { bundleId in
+                guard let app = runningApplication(bundleId: bundleId) else { return true }
+                return !app.isFinishedLaunching
+            }
+        }
+        if !pending.isEmpty {
+            Log.warning("[launch] 起動待機タイムアウト: \(pending.sorted())")
+        }
+
+        var result: [String: pid_t] = [:]
+        for bundleId in bundleIds {
+            if let app = runningApplication(bundleId: bundleId) {
+                result[bundleId] = app.processIdentifier
+            }
+        }
+        return result
+    }
+
+    /// bundle id からアプリを起動（非アクティブで起動要求のみ行う）
+    /// 戻り値: 起動要求を出せた場合 true
+    @discardableResult
+    static func launch(bundleId: String) -> Bool {
+        guard let url = NSWorkspace.shared.urlForApplication(withBundleIdentifier: bundleId) else {
+            Log.warning("[launch] アプリが見つかりません: \(bundleId)")
+            return false
+        }
+        let configuration = NSWorkspace.OpenConfiguration()
+        configuration.activates = false
+        NSWorkspace.shared.openApplication(at: url, configuration: configuration) { _, error in
+            if let error = error {
+                Log.warning("[launch] 起動失敗 \(bundleId): \(error.localizedDescription)")
+            }
+        }
+        Log.debug("[launch] 起動要求: \(bundleId)")
+        return true
+    }
+}
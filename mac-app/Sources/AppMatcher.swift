@@ -0,0 +1,90 @@
+import Foundation
+
+/// アプリ指定（exclude_apps / include_apps）のパターン照合
+/// - `com.apple.finder` / `Finder`: bundle id の完全一致、またはアプリ名の一致（大文字小文字を区別しない）
+/// - `com.jetbrains.*` / `*Helper*`: ワイルドカード（`*` と `?`）で bundle id またはアプリ名に一致
+/// - `name~"Helper$"`: アプリ名に対する正規表現
+/// - `bundle~"^com\.electron\."`: bundle id に対する正規表現
+struct AppMatcher {
+    private enum Rule {
+        case exact(String)
+        case any(NSRegularExpression)
+        case name(NSRegularExpression)
+        case bundle(NSRegularExpression)
+    }
+
+    private let rules: [Rule]
+    /// 解釈できなかったパターン（不正な正規表現など）
+    let invalidPatterns: [String]
+
+    init(patterns: [String]) {
+        var rules: [Rule] = []
+        var invalid: [String] = []
+        for entry in patterns {
+            let pattern = entry.trimmingCharacters(in: .whitespacesAndNewlines)
+            if pattern.isEmpty { continue }
+            if let rule = AppMatcher.compile(pattern) {
+                rules.append(rule)
+            } else {
+                invalid.append(pattern)
+            }
+        }
+        self.rules = rules
+        self.invalidPatterns = invalid
+    }
+
+    func matches(bundleId: String?, ownerName: String) -> Bool {
+        return rules.contains { rule in
+            switch rule {
+            case .exact(let pattern):
+                return bundleId == pattern || ownerName.caseInsensitiveCompare(pattern) == .orderedSame
+            case .any(let regex):
+                return AppMatcher.test(regex, bundleId) || AppMatcher.test(regex, ownerName)
+            case .name(let regex):
+                return AppMatcher.test(regex, ownerName)
+            case .bundle(let regex):
+                return AppMatcher.test(regex, bundleId)
+            }
+        }
+    }
+
+    // MARK: - キャッシュ
+
+    private static var cache: [[String]: AppMatcher] = [:]
+    private static let cacheLock = NSLock()
+
+    /// 同じパターン一覧はコンパイル済みの照合器を再利用する（設定はスキャン/復元のたびに読み込まれるため）
+    static func cached(_ patterns: [String]) -> AppMatcher {
+        cacheLock.lock()
+        defer { cacheLock.unlock() }
+        if let matcher = cache[patterns] { return matcher }
+        let matcher = AppMatcher(patterns: patterns)
+        cache[patterns] = matcher
+        return matcher
+    }
+
+    // MARK: - 解釈
+
+    private static func compile(_ pattern: String) -> Rule? {
+        for (prefix, make) in [("name~", Rule.name), ("bundle~", Rule.bundle)] as [(String, (NSRegularExpression) -> Rule)] {
+            guard pattern.lowercased().hasPrefix(prefix) else { continue }
+            var body = pattern.dropFirst(prefix.count).trimmingCharacters(in: .whitespaces)
+            if body.count >= 2, body.hasPrefix("\""), body.hasSuffix("\"") {
+                body = String(body.dropFirst().dropLast())
+            }
+            return (try? NSRegularExpression(pattern: body, options: [.caseInsensitive])).map(make)
+        }
+        if pattern.contains("*") || pattern.contains("?") {
+            let escaped = NSRegularExpression.escapedPattern(for: pattern)
+                .replacingOccurrences(of: "\\*", with: ".*")
+                .replacingOccurrences(of: "\\?", with: ".")
+            return (try? NSRegularExpression(pattern: "^\(escaped)$", options: [.caseInsensitive])).map(Rule.any)
+        }
+        return .exact(pattern)
+    }
+
+    private static func test(_ regex: NSRegularExpression, _ text: String?) -> Bool {
+        guard let text = text else { return false }
+        return regex.firstMatch(in: text, range: NSRange(text.startIndex..., in: text)) != nil
+    }
+}
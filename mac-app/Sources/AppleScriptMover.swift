@@ -0,0 +1,102 @@
+import Foundation
+import CoreGraphics
+import CoreServices
+
+/// AppleScript（System Events）によるウィンドウ配置
+/// - AX で位置/サイズを設定できないアプリ向けのフォールバック（オートメーション権限が必要）
+/// - アプリはローカライズ名ではなく unix id（PID）で指定する
+/// - 同じプロセスの複数ウィンドウは1本のスクリプトでまとめて配置する
+enum AppleScriptMover {
+
+    /// 配置要求1件分（タイトルが一致するウィンドウ、無ければ先頭ウィンドウを対象にする）
+    struct Move {
+        let windowTitle: String?
+        let frame: CGRect
+    }
+
+    /// スクリプト実行結果
+    struct Outcome {
+        /// moves と同じ順の成否（スクリプト自体が失敗した場合はすべて false）
+        let results: [Bool]
+        /// オートメーション権限が無く System Events を操作できなかったか
+        let automationDenied: Bool
+    }
+
+    // NSAppleScript はスレッドセーフではないため、並行復元時も1件ずつ実行する
+    private static let lock = NSLock()
+
+    /// 指定プロセスのウィンドウを1件移動・リサイズ
+    /// 戻り値: 配置できた場合 true
+    @discardableResult
+    static func setFrame(pid: pid_t, windowTitle: String?, frame: CGRect) -> Bool {
+        return setFrames(pid: pid, moves: [Move(windowTitle: windowTitle, frame: frame)]).first ?? false
+    }
+
+    /// 指定プロセスの複数ウィンドウを1回のスクリプト実行で移動・リサイズ
+    /// 戻り値: moves と同じ順の成否（スクリプト自体が失敗した場合はすべて false）
+    static func setFrames(pid: pid_t, moves: [Move]) -> [Bool] {
+        return perform(pid: pid, moves: moves).results
+    }
+
+    /// setFrames と同じ処理を行い、失敗がオートメーション権限によるものかも返す
+    static func perform(pid: pid_t, moves: [Move]) -> Outcome {
+        guard !moves.isEmpty else { return Outcome(results: [], automationDenied: false) }
+        // ウィンドウごとに try で囲み、1件の失敗で残りが止まらないようにする
+        let body = moves.map { move -> String in
+            let frame = move.frame
+            return """
+                    try
+                        set w to \(windowReference(for: move.windowTitle))
+                        set position of w to {\(Int(frame.minX)), \(Int(frame.minY))}
+                        set size of w to {\(Int(frame.width)), \(Int(frame.height))}
+                        set end of results to true
+                    on error
+                        set end of results to false
+                    end try
+            """
+        }.joined(separator: "\n")
+        let source = """
+        set results to {}
+        tell application "System Events"
+            tell (first application process whose unix id is \(pid))
+        \(body)
+            end tell
+        end tell
+        return results
+        """
+
+        lock.lock()
+        defer { lock.unlock() }
+        let failed = [Bool](repeating: false, count: moves.count)
+        guard let script = NSAppleScript(source: source) else { return Outcome(results: failed, automationDenied: false) }
+        var errorInfo: NSDictionary?
+        let descriptor = script.executeAndReturnError(&errorInfo)
+        if let errorInfo = errorInfo {
+            // -1743 (errAEEventNotPermitted): システム設定でオートメーションが許可されていない
+            if (errorInfo[NSAppleScript.errorNumber] as? Int) == Int(errAEEventNotPermitted) {
+                Log.warning("[restore] オートメーション権限が無いため AppleScript で配置できません pid=\(pid)（システム設定 > プライバシーとセキュリティ > オートメーション）")
+                return Outcome(results: failed, automationDenied: true)
+            }
+            let message = errorInfo[NSAppleScript.errorMessage] as? String ?? "\(errorInfo)"
+            Log.warning("[restore] AppleScript による配置に失敗 pid=\(pid): \(message)")
+            return Outcome(results: failed, automationDenied: false)
+        }
+        let results = moves.indices.map { i -> Bool in
+            // AppleScript のリストは 1 始まり
+            descriptor.atIndex(i + 1)?.booleanValue ?? false
+        }
+        Log.debug("[restore] AppleScript で配置しました pid=\(pid): \(results.filter { $0 }.count)/\(moves.count)")
+        return Outcome(results: results, automationDenied: false)
+    }
+
+    private static func windowReference(for title: String?) -> String {
+        guard let title = title, !title.isEmpty else { return "window 1" }
+        return "(first window whose name is \"\(escaped(title))\")"
+    }
+
+    // AppleScript 文字列リテラル用のエスケープ
+    private static func escaped(_ text: String) -> String {
+        return text.replacingOccurrences(of: "\\", with: "\\\\")
+            .replacingOccurrences(of: "\"", with: "\\\"")
+    }
+}
@@ -0,0 +1,85 @@
+import Foundation
+
+/// 一定間隔でウィンドウ配置を自動スナップショット（auto_<日時>）として保存する
+/// - 間隔は開始時の Config.scanIntervalMs（開始時に指定した場合はその値）
+/// - 前回の保存から配置が変わっていない場合は保存しない（古いスナップショットの削除は saveAutoSnapshot の保持ポリシーに従う）
+final class AutoSaveScheduler {
+    private let queue = DispatchQueue(label: "window_restore.auto-save")
+    private var timer: DispatchSourceTimer?
+    private var lastWindows: [WindowInfo]?
+    private var interval: UInt64 = 0
+
+    /// 保存間隔の上限（24 時間）。これを超える間隔は上限に丸める
+    static let maxIntervalMs: UInt64 = 24 * 60 * 60 * 1000
+
+    deinit {
+        stop()
+    }
+
+    var isRunning: Bool {
+        return queue.sync { timer != nil }
+    }
+
+    /// 実行中の保存間隔（停止中は nil）
+    var intervalMs: UInt64? {
+        return queue.sync { timer != nil ? interval : nil }
+    }
+
+    // MARK: - 開始/停止
+
+    /// 開始（実行中の場合は新しい間隔で開始し直す）
+    func start(intervalMs: UInt64? = nil) {
+        var interval = intervalMs ?? Config.load().scanIntervalMs
+        if interval > Self.maxIntervalMs {
+            // DispatchTimeInterval は Int のため、大きすぎる間隔はそのまま渡せない
+            Log.warning("[layout] 自動保存の間隔 \(interval) ms が上限を超えるため \(Self.maxIntervalMs) ms にします")
+            interval = Self.maxIntervalMs
+        }
+        queue.sync {
+            timer?.cancel()
+            lastWindows = nil
+            self.interval = interval
+            let source = DispatchSource.makeTimerSource(queue: queue)
+            source.schedule(deadline: .now() + .milliseconds(Int(interval)),
+                            repeating: .milliseconds(Int(interval)),
+                            leeway: .milliseconds(Int(interval / 10)))
+            source.setEventHandler { [weak self] in self?.tick() }
+            source.resume()
+            timer = source
+        }
+        Log.info("[layout] 自動保存を開始しました（間隔 \(interval) ms）")
+    }
+
+    func stop() {
+        queue.sync {
+            guard let timer = timer else { return }
+            timer.cancel()
+            self.timer = nil
+            lastWindows = nil
+            Log.info("[layout] 自動保存を停止しました")
+        }
+    }
+
+    // MARK: - 保存
+
+    private func tick() {
+        let manager = WindowManager.shared
+        // 権限が取り消された場合は保存せず一時停止する（停止処理はメインスレッドで行われる）
+        guard manager.hasAccessibilityPermission() else {
+            manager.handlePermissionRevoked()
+            return
+        }
+        if let previous = lastWindows {
+            let (diff, current) = manager.diff(previous: previous)
+            guard !diff.isEmpty else { return }
+            lastWindows = current
+        } else {
+            lastWindows = manager.fetchVisibleAppWindows()
+        }
+        do {
+            try manager.saveAutoSnapshot()
+        } catch {
+            Log.warning("[layout] 自動保存に失敗: \(error.localizedDescription)")
+        }
+    }
+}
@@ -0,0 +1,524 @@
+import Foundation
+import CoreGraphics
+
+/// アプリケーション設定（`<base>/config.json`。config.yaml / config.toml も可）
+/// - プロファイルを選択している場合は `<base>/profiles/<name>.json`
+/// - キーは snake_case（REQUIREMENTS.md 3.2 に準拠）
+/// - 未知/欠落キーは既定値で補完する
+struct Config: Codable {
+    /// 幅・高さの組（ピクセル）
+    struct WindowSize: Codable, Equatable {
+        var width: Double
+        var height: Double
+    }
+
+    /// 対象アプリの指定方法
+    enum AppFilterMode: String, Codable {
+        /// 除外リスト（exclude_apps）以外のすべてのアプリ
+        case exclude
+        /// 対象リスト（include_apps）のアプリのみ
+        case include
+    }
+
+    /// 集中モード中の通知の扱い
+    enum FocusNotificationMode: String, Codable {
+        /// そのまま表示する
+        case deliver
+        /// 表示しない
+        case suppress
+        /// 集中モードの終了後にまとめて表示する
+        case queue
+    }
+
+    /// アプリ別の上書き設定（未指定の項目は全体の設定に従う）
+    struct AppOverride: Codable, Equatable {
+        /// 起動後、ウィンドウ配置を始めるまでの待機時間（ミリ秒）
+        var restoreDelayMs: UInt64?
+        /// 保存済みウィンドウとライブウィンドウの対応付け方法
+        var matching: WindowMatchingStrategy?
+        /// 未起動の場合に起動しないか
+        var skipLaunch: Bool?
+        /// 位置のみ復元し、サイズは変更しないか（サイズを自前で管理するアプリ向け）
+        var positionOnly: Bool?
+
+        enum CodingKeys: String, CodingKey {
+            case restoreDelayMs = "restore_delay_ms"
+            case matching
+            case skipLaunch = "skip_launch"
+            case positionOnly = "position_only"
+        }
+    }
+
+    /// ログイン時に自動復元するか
+    var autoRestore: Bool = false
+    /// ディスプレイ構成の変化を検知するか
+    var displayChangeDetection: Bool = true
+    /// 保存/復元から除外するアプリ（bundle id またはアプリ名。ワイルドカード/正規表現も可）
+    var excludeApps: [String] = ["com.apple.finder"]
+    /// 非表示ウィンドウを最小化扱いにするか
+    var minimizeHiddenWindows: Bool = true
+    /// アプリ起動後、ウィンドウ配置を始めるまでの待機時間（ミリ秒）
+    var restoreDelayMs: UInt64 = 1000
+    /// ウィンドウ検出/配置に失敗した場合の最大リトライ回数
+    var maxRetryAttempts: UInt32 = 3
+    /// スキャン間隔（ミリ秒）
+    var scanIntervalMs: UInt64 = 5000
+    /// メモリ使用量上限（MB）
+    var maxMemoryUsageMb: UInt64 = 50
+    /// ユーザーに見えないウィンドウ（透明オーバーレイ、通常レイヤー以外、バックグラウンド専用プロセス）を除外するか
+    var skipInvisibleWindows: Bool = true
+    /// この値未満の不透明度（kCGWindowAlpha）のウィンドウを不可視とみなす
+    var minWindowAlpha: Double = 0.05
+    /// スキャン結果キャッシュの有効期間（ミリ秒。0でキャッシュ無効）
+    var scanCacheTtlMs: UInt64 = 2000
+    /// メニューバー常駐/エージェントアプリ（LSUIElement 等）のウィンドウもスキャン対象に含めるか
+    var includeAgentApps: Bool = false
+    /// 保存対象とする最小ウィンドウサイズ（幅・高さのいずれかが下回るウィンドウは除外）
+    var minWindowSize = WindowSize(width: 100, height: 100)
+    /// フルスクリーンで保存されたウィンドウをフルスクリーンに戻すか（false の場合は表示領域いっぱいの通常ウィンドウとして復元）
+    var restoreFullscreen: Bool = true
+    /// 復元時に並行処理するアプリ数の上限（同じアプリのウィンドウは常に順に処理する）
+    var maxParallelApps: Int = 4
+    /// リトライ初回の待機時間（ミリ秒）
+    var retryInitialDelayMs: UInt64 = 500
+    /// リトライごとの待機時間の倍率（1.0 で固定間隔）
+    var retryBackoffMultiplier: Double = 2.0
+    /// リトライ待機時間の上限（ミリ秒）
+    var retryMaxDelayMs: UInt64 = 5000
+    /// 復元時に未起動のアプリを起動するか（false の場合は起動中のアプリのウィンドウのみ復元）
+    var launchMissingApps: Bool = true
+    /// 復元全体の制限時間（ミリ秒。超えた場合は残りの処理を打ち切る。0で無制限）
+    var restoreTimeoutMs: UInt64 = 120000
+    /// アプリごとの起動後待機時間（bundle id → ミリ秒。restore_delay_ms より優先）
+    var appRestoreDelaysMs: [String: UInt64] = [:]
+    /// ウィンドウを一度に移動せず、補間しながら段階的に動かすか
+    var animateWindowMoves: Bool = false
+    /// 段階的な移動にかける時間（ミリ秒）
+    var animationDurationMs: UInt64 = 200
+    /// レイアウト上書き時に残す過去世代の数（<name>.json.1 が最新。0でバックアップしない）
+    var layoutBackupCount: Int = 3
+    /// レイアウトの保存先（file: レイアウトごとの JSON ファイル、sqlite: 単一の SQLite データベース）
+    var storageBackend: LayoutStorageBackend = .file
+    /// レイアウトを暗号化して保存するか（AES-GCM。鍵はキーチェーンに保管。既存ファイルは次回保存時に暗号化される）
+    var encryptLayouts: Bool = false
+    /// レイアウトの保存先フォルダ（iCloud Drive など同期フォルダを指定可能。nil の場合は <base>/layouts。storage_backend が file の場合のみ）
+    var layoutsDirectory: String? = nil
+    /// 新規レイアウトを保存する形式（json / yaml / toml。既存のレイアウトは元の形式のまま更新する）
+    var layoutFormat: SerializationFormat = .json
+    /// 自動スナップショットを残す件数（超えた分は古い順に削除。0で件数制限なし）
+    var autoSnapshotKeep: Int = 20
+    /// 自動スナップショットの保持日数（これより古いものは削除。0で期間制限なし）
+    var autoSnapshotMaxAgeDays: Int = 7
+    /// 保存しておくレイアウト数の上限（超えた場合は固定していないものを使われていない順に削除。自動スナップショットを優先して削除する。0で無制限）
+    /// - 既定は無制限（ユーザーが保存したレイアウトを勝手に削除しないように。自動スナップショットは auto_snapshot_keep で別に間引く）
+    var maxLayouts: Int = 0
+    /// アプリごとの上書き設定（bundle id → 設定。未指定の項目は全体の設定に従う）
+    var appOverrides: [String: AppOverride] = [:]
+    /// 対象アプリの指定方法（exclude: exclude_apps 以外のすべて、include: include_apps のみ）
+    var appFilterMode: AppFilterMode = .exclude
+    /// app_filter_mode が include の場合に保存/復元の対象とするアプリ（bundle id またはアプリ名）
+    var includeApps: [String] = []
+    /// 引数なしの復元（restore_default）で使うレイアウト名（ショートカットやログイン時の復元、メニューの「既定のレイアウトを復元」）
+    var defaultLayout: String? = nil
+    /// 出力するログの最低レベル（debug / info / warning / error）
+    var logLevel: LogLevel = .info
+    /// ログの書き出し先ファイル（~ 可。nil の場合は標準出力のみ）
+    var logFile: String? = nil
+    /// ログファイルをローテーションするサイズ（KB。0でローテーションしない）
+    var logMaxSizeKb: UInt64 = 1024
+    /// ローテーションで残す過去のログファイル数（<file>.1 が最新）
+    var logMaxFiles: Int = 3
+    /// ディスプレイの別名（UUID → 名前。ログ・通知・画面でモニターを名前で表示する）
+    var displayAliases: [String: String] = [:]
+    /// ディスプレイの接続/取り外し時に、新しい構成と同じディスプレイ配置で保存されたレイアウトを自動で復元するか（display_change_detection が有効な場合のみ）
+    var autoRestoreOnDisplayChange: Bool = false
+    /// 仮想ディスプレイ（Sidecar、AirPlay、DisplayLink など）をレイアウトの対象外にするか（保存時はそこにあるウィンドウを記録せず、復元時は配置先にしない）
+    var excludeVirtualDisplays: Bool = false
+    /// 同じ内容の通知をまとめる間隔（秒。この間に同じタイトル・本文の通知が続いた場合は1件にまとめる。0でまとめない）
+    var notificationDedupSeconds: UInt64 = 30
+    /// 1分間に表示する通知の上限（超えた分は表示しない。0で無制限）
+    var notificationMaxPerMinute: Int = 10
+    /// 集中モード（おやすみモードを含む）中の重要でない通知の扱い（deliver: そのまま表示、suppress: 表示しない、queue: 集中モードの終了後にまとめて表示。警告は常に表示する）
+    var notificationsDuringFocus: FocusNotificationMode = .deliver
+    /// 設定ファイルの形式のバージョン（古い形式は読み込み時に移行して書き戻す）
+    var version: Int = Config.currentVersion
+
+    /// 読み込んだファイルに含まれていた未知の項目（保存しない。検証で報告する）
+    var unknownKeys: [String] = []
+
+    /// 現在の設定ファイルの形式のバージョン
+    static let currentVersion = 1
+
+    enum CodingKeys: String, CodingKey, CaseIterable {
+        case autoRestore = "auto_restore"
+        case displayChangeDetection = "display_change_detection"
+        case excludeApps = "exclude_apps"
+        case minimizeHiddenWindows = "minimize_hidden_windows"
+        case restoreDelayMs = "restore_delay_ms"
+        case maxRetryAttempts = "max_retry_attempts"
+        case scanIntervalMs = "scan_interval_ms"
+        case maxMemoryUsageMb = "max_memory_usage_mb"
+        case skipInvisibleWindows = "skip_invisible_windows"
+        case minWindowAlpha = "min_window_alpha"
+        case scanCacheTtlMs = "scan_cache_ttl_ms"
+        case includeAgentApps = "include_agent_apps"
+        case minWindowSize = "min_window_size"
+        case restoreFullscreen = "restore_fullscreen"
+        case maxParallelApps = "max_parallel_apps"
+        case retryInitialDelayMs = "retry_initial_delay_ms"
+        case retryBackoffMultiplier = "retry_backoff_multiplier"
+        case retryMaxDelayMs = "retry_max_delay_ms"
+        case launchMissingApps = "launch_missing_apps"
+        case restoreTimeoutMs = "restore_timeout_ms"
+        case appRestoreDelaysMs = "app_restore_delays_ms"
+        case animateWindowMoves = "animate_window_moves"
+        case animationDurationMs = "animation_duration_ms"
+        case layoutBackupCount = "layout_backup_count"
+        case storageBackend = "storage_backend"
+        case encryptLayouts = "encrypt_layouts"
+        case layoutsDirectory = "layouts_directory"
+        case layoutFormat = "layout_format"
+        case autoSnapshotKeep = "auto_snapshot_keep"
+        case autoSnapshotMaxAgeDays = "auto_snapshot_max_age_days"
+        case maxLayouts = "max_layouts"
+        case appOverrides = "app_overrides"
+        case appFilterMode = "app_filter_mode"
+        case includeApps = "include_apps"
+        case defaultLayout = "default_layout"
+        case logLevel = "log_level"
+        case logFile = "log_file"
+        case logMaxSizeKb = "log_max_size_kb"
+        case logMaxFiles = "log_max_files"
+        case displayAliases = "display_aliases"
+        case autoRestoreOnDisplayChange = "auto_restore_on_display_change"
+        case excludeVirtualDisplays = "exclude_virtual_displays"
+        case notificationDedupSeconds = "notification_dedup_seconds"
+        case notificationMaxPerMinute = "notification_max_per_minute"
+        case notificationsDuringFocus = "notifications_during_focus"
+        case version = "version"
+    }
+
+    init() {}
+
+    init(from decoder: Decoder) throws {
+        let c = try decoder.container(keyedBy: CodingKeys.self)
+        let d = Config()
+        autoRestore = try c.decodeIfPresent(Bool.self, forKey: .autoRestore) ?? d.autoRestore
+        displayChangeDetection = try c.decodeIfPresent(Bool.self, forKey: .displayChangeDetection) ?? d.displayChangeDetection
+        excludeApps = try c.decodeIfPresent([String].self, forKey: .excludeApps) ?? d.excludeApps
+        minimizeHiddenWindows = try c.decodeIfPresent(Bool.self, forKey: .minimizeHiddenWindows) ?? d.minimizeHiddenWindows
+        restoreDelayMs = try c.decodeIfPresent(UInt64.self, forKey: .restoreDelayMs) ?? d.restoreDelayMs
+        maxRetryAttempts = try c.decodeIfPresent(UInt32.self, forKey: .maxRetryAttempts) ?? d.maxRetryAttempts
+        scanIntervalMs = try c.decodeIfPresent(UInt64.self, forKey: .scanIntervalMs) ?? d.scanIntervalMs
+        maxMemoryUsageMb = try c.decodeIfPresent(UInt64.self, forKey: .maxMemoryUsageMb) ?? d.maxMemoryUsageMb
+        skipInvisibleWindows = try c.decodeIfPresent(Bool.self, forKey: .skipInvisibleWindows) ?? d.skipInvisibleWindows
+        minWindowAlpha = try c.decodeIfPresent(Double.self, forKey: .minWindowAlpha) ?? d.minWindowAlpha
+        scanCacheTtlMs = try c.decodeIfPresent(UInt64.self, forKey: .scanCacheTtlMs) ?? d.scanCacheTtlMs
+        includeAgentApps = try c.decodeIfPresent(Bool.self, forKey: .includeAgentApps) ?? d.includeAgentApps
+        minWindowSize = try c.decodeIfPresent(WindowSize.self, forKey: .minWindowSize) ?? d.minWindowSize
+        restoreFullscreen = try c.decodeIfPresent(Bool.self, forKey: .restoreFullscreen) ?? d.restoreFullscreen
+        maxParallelApps = try c.decodeIfPresent(Int.self, forKey: .maxParallelApps) ?? d.maxParallelApps
+        retryInitialDelayMs = try c.decodeIfPresent(UInt64.self, forKey: .retryInitialDelayMs) ?? d.retryInitialDelayMs
+        retryBackoffMultiplier = try c.decodeIfPresent(Double.self, forKey: .retryBackoffMultiplier) ?? d.retryBackoffMultiplier
+        retryMaxDelayMs = try c.decodeIfPresent(UInt64.self, forKey: .retryMaxDelayMs) ?? d.retryMaxDelayMs
+        launchMissingApps = try c.decodeIfPresent(Bool.self, forKey: .launchMissingApps) ?? d.launchMissingApps
+        restoreTimeoutMs = try c.decodeIfPresent(UInt64.self, forKey: .restoreTimeoutMs) ?? d.restoreTimeoutMs
+        appRestoreDelaysMs = try c.decodeIfPresent([String: UInt64].self, forKey: .appRestoreDelaysMs) ?? d.appRestoreDelaysMs
+        animateWindowMoves = try c.decodeIfPresent(Bool.self, forKey: .animateWindowMoves) ?? d.animateWindowMoves
+        animationDurationMs = try c.decodeIfPresent(UInt64.self, forKey: .animationDurationMs) ?? d.animationDurationMs
+        layoutBackupCount = try c.decodeIfPresent(Int.self, forKey: .layoutBackupCount) ?? d.layoutBackupCount
+        storageBackend = try c.decodeIfPresent(LayoutStorageBackend.self, forKey: .storageBackend) ?? d.storageBackend
+        encryptLayouts = try c.decodeIfPresent(Bool.self, forKey: .encryptLayouts) ?? d.encryptLayouts
+        layoutsDirectory = try c.decodeIfPresent(String.self, forKey: .layoutsDirectory) ?? d.layoutsDirectory
+        layoutFormat = try c.decodeIfPresent(SerializationFormat.self, forKey: .layoutFormat) ?? d.layoutFormat
+        autoSnapshotKeep = try c.decodeIfPresent(Int.self, forKey: .autoSnapshotKeep) ?? d.autoSnapshotKeep
+        autoSnapshotMaxAgeDays = try c.decodeIfPresent(Int.self, forKey: .autoSnapshotMaxAgeDays) ?? d.autoSnapshotMaxAgeDays
+        maxLayouts = try c.decodeIfPresent(Int.self, forKey: .maxLayouts) ?? d.maxLayouts
+        appOverrides = try c.decodeIfPresent([String: AppOverride].self, forKey: .appOverrides) ?? d.appOverrides
+        appFilterMode = try c.decodeIfPresent(AppFilterMode.self, forKey: .appFilterMode) ?? d.appFilterMode
+        includeApps = try c.decodeIfPresent([String].self, forKey: .includeApps) ?? d.includeApps
+        defaultLayout = try c.decodeIfPresent(String.self, forKey: .defaultLayout) ?? d.defaultLayout
+        logLevel = try c.decodeIfPresent(LogLevel.self, forKey: .logLevel) ?? d.logLevel
+        logFile = try c.decodeIfPresent(String.self, forKey: .logFile) ?? d.logFile
+        logMaxSizeKb = try c.decodeIfPresent(UInt64.self, forKey: .logMaxSizeKb) ?? d.logMaxSizeKb
+        logMaxFiles = try c.decodeIfPresent(Int.self, forKey: .logMaxFiles) ?? d.logMaxFiles
+        displayAliases = try c.decodeIfPresent([String: String].self, forKey: .displayAliases) ?? d.displayAliases
+        autoRestoreOnDisplayChange = try c.decodeIfPresent(Bool.self, forKey: .autoRestoreOnDisplayChange) ?? d.autoRestoreOnDisplayChange
+        excludeVirtualDisplays = try c.decodeIfPresent(Bool.self, forKey: .excludeVirtualDisplays) ?? d.excludeVirtualDisplays
+        notificationDedupSeconds = try c.decodeIfPresent(UInt64.self, forKey: .notificationDedupSeconds) ?? d.notificationDedupSeconds
+        notificationMaxPerMinute = try c.decodeIfPresent(Int.self, forKey: .notificationMaxPerMinute) ?? d.notificationMaxPerMinute
+        notificationsDuringFocus = try c.decodeIfPresent(FocusNotificationMode.self, forKey: .notificationsDuringFocus) ?? d.notificationsDuringFocus
+        // バージョンの無い設定は移行前（0）として扱う
+        version = try c.decodeIfPresent(Int.self, forKey: .version) ?? 0
+        let known = Set(CodingKeys.allCases.map(\.rawValue))
+        unknownKeys = try decoder.container(keyedBy: AnyCodingKey.self).allKeys
+            .map(\.stringValue).filter { !known.contains($0) }.sorted()
+    }
+
+    // MARK: - 読み込み/保存
+
+    /// 設定を読み込み（ファイルが無ければ旧 UserDefaults の値、読み込み失敗時は既定値）
+    /// - applyEnvironment が true の場合は WINDOW_RESTORE_* 環境変数の上書きを適用する（ConfigEnvironment.swift）
+    /// - 読み込んだ値は検証し、repair が true の場合は不正な項目を既定値に戻す（ファイルは書き換えない）
+    /// - 既定の引数での読み込み結果はメモリに保持し、設定ファイルのパスか更新日時が変わるまで読み直さない
+    static func load(repair: Bool = true, applyEnvironment: Bool = true) -> Config {
+        let useCache = repair && applyEnvironment
+        if useCache, let cached = cachedConfig() {
+            return cached
+        }
+        var config = loadFile()
+        if applyEnvironment {
+            config = config.applyingEnvironment()
+        }
+        let errors = config.validate()
+        reportValidationErrors(errors)
+        let result = repair && !errors.isEmpty ? config.repaired(errors) : config
+        if useCache {
+            storeCache(result)
+        }
+        return result
+    }
+
+    // MARK: - キャッシュ
+
+    private static var cache: (url: URL, modified: Date?, config: Config)?
+    private static let cacheLock = NSLock()
+
+    /// 保持している設定を破棄（次の load で設定ファイルを読み直す）
+    /// - 保存時と ConfigWatcher が変更を検知したときに呼ばれる
+    static func invalidateCache() {
+        cacheLock.lock()
+        defer { cacheLock.unlock() }
+        cache = nil
+    }
+
+    private static func cachedConfig() -> Config? {
+        let url = FileHelper.configFileURL()
+        let modified = modificationDate(of: url)
+        cacheLock.lock()
+        defer { cacheLock.unlock() }
+        guard let cache = cache, cache.url == url, cache.modified == modified else { return nil }
+        return cache.config
+    }
+
+    private static func storeCache(_ config: Config) {
+        let url = FileHelper.configFileURL()
+        let modified = modificationDate(of: url)
+        cacheLock.lock()
+        defer { cacheLock.unlock() }
+        cache = (url, modified, config)
+    }
+
+    private static func modificationDate(of url: URL) -> Date? {
+        return (try? FileManager.default.attributesOfItem(atPath: url.path))?[.modificationDate] as? Date
+    }
+
+    // 設定ファイルを読み込み、古い形式なら移行して書き戻す
+    private static func loadFile() -> Config {
+        let url = FileHelper.configFileURL()
+        guard FileManager.default.fileExists(atPath: url.path) else {
+            return migratedFromUserDefaults()
+        }
+        var config: Config
+        do {
+            config = try FileHelper.load(Config.self, from: url)
+        } catch {
+            Log.error("[config] 設定の読み込みに失敗したため既定値を使用します: \(error.localizedDescription)")
+            return Config()
+        }
+        if config.version < Config.currentVersion {
+            // 別プロセスの変更と競合しないよう Config.update と同じロックの中で読み直して移行する
+            do {
+                config = try Config.withFileLock {
+                    var current = try FileHelper.load(Config.self, from: url)
+                    // ロック待ちの間に別プロセスが移行済みならそのまま使う
+                    guard current.version < Config.currentVersion else { return current }
+                    let from = current.version
+                    current.migrate()
+                    // 移行前のファイルは <name>.1 として残す
+                    try FileHelper.rotateBackups(of: url, keep: 1)
+                    try current.save()
+                    Log.info("[config] 設定ファイルをバージョン \(from) から \(current.version) へ移行しました")
+                    return current
+                }
+            } catch {
+                Log.warning("[config] 移行した設定の保存に失敗: \(error.localizedDescription)")
+                config.migrate()
+            }
+        }
+        return config
+    }
+
+    private static var lastReportedErrors: [ConfigValidationError] = []
+    private static let reportLock = NSLock()
+
+    // 読み込みのたびに同じ内容を出力しないよう、前回から変わった場合のみログに出す
+    private static func reportValidationErrors(_ errors: [ConfigValidationError]) {
+        reportLock.lock()
+        defer { reportLock.unlock() }
+        guard errors != lastReportedErrors else { return }
+        lastReportedErrors = errors
+        for error in errors {
+            Log.warning("[config] \(error.message)")
+        }
+    }
+
+    func save() throws {
+        defer { Config.invalidateCache() }
+        let url = FileHelper.configFileURL()
+        // TOML は説明コメント付きで書き出す
+        if SerializationFormat(url: url) == .toml {
+            try FileHelper.ensureParentDirectory(of: url)
+            try Data(annotatedTOML().utf8).write(to: url, options: [.atomic])
+            return
+        }
+        try FileHelper.save(self, to: url)
+    }
+
+    // MARK: - バージョン移行
+
+    /// 古い形式の設定を現在の形式へ移行（バージョンごとに順に適用する）
+    mutating func migrate() {
+        if version < 1 {
+            // v1: アプリ別の待機時間（app_restore_delays_ms）を app_overrides に統合
+            for (bundleId, delay) in appRestoreDelaysMs where appOverrides[bundleId]?.restoreDelayMs == nil {
+                appOverrides[bundleId, default: AppOverride()].restoreDelayMs = delay
+            }
+            appRestoreDelaysMs = [:]
+            version = 1
+        }
+    }
+
+    // MARK: - プロファイル
+
+    /// 指定プロファイル（`<base>/profiles/<name>.json`）を読み込み
+    static func loadProfile(_ name: String) throws -> Config {
+        let url = try FileHelper.profileFileURL(name: name)
+        guard FileManager.default.fileExists(atPath: url.path) else { throw FileHelper.FileHelperError.profileNotFound }
+        return try FileHelper.load(Config.self, from: url)
+    }
+
+    /// この設定をプロファイルとして保存
+    func save(asProfile name: String) throws {
+        try FileHelper.save(self, to: FileHelper.profileFileURL(name: name))
+    }
+
+    /// 選択中のプロファイル名（nil は既定の config.json。選択中のプロファイルが見つからない場合も nil）
+    static var activeProfile: String? {
+        guard let name = FileHelper.activeProfileName(),
+              let url = try? FileHelper.profileFileURL(name: name),
+              FileManager.default.fileExists(atPath: url.path) else { return nil }
+        return name
+    }
+
+    /// 使用するプロファイルを切り替え（nil で既定の config.json に戻す）
+    static func setActiveProfile(_ name: String?) throws {
+        let url = FileHelper.activeProfileURL()
+        guard let name = name else {
+            if FileManager.default.fileExists(atPath: url.path) {
+                try FileManager.default.removeItem(at: url)
+            }
+            Log.info("[config] 既定の設定に切り替えました")
+            return
+        }
+        guard FileManager.default.fileExists(atPath: try FileHelper.profileFileURL(name: name).path) else {
+            throw FileHelper.FileHelperError.profileNotFound
+        }
+        try FileHelper.ensureDirectories()
+        try Data(name.utf8).write(to: url, options: [.atomic])
+        Log.info("[config] プロファイル「\(name)」に切り替えました")
+    }
+
+    static func listProfiles() -> [String] {
+        return FileHelper.listProfileNames()
+    }
+
+    // 旧バージョン（設定画面が UserDefaults に保存していた値）からの移行
+    private static func migratedFromUserDefaults() -> Config {
+        let defaults = UserDefaults.standard
+        var config = Config()
+        if defaults.object(forKey: "autoRestore") != nil {
+            config.autoRestore = defaults.bool(forKey: "autoRestore")
+        }
+        if defaults.object(forKey: "detectDisplayChanges") != nil {
+            config.displayChangeDetection = defaults.bool(forKey: "detectDisplayChanges")
+        }
+        let restoreDelay = defaults.integer(forKey: "restoreDelay")
+        if restoreDelay > 0 {
+            config.restoreDelayMs = UInt64(restoreDelay)
+        }
+        if let excludedApps = defaults.array(forKey: "excludedApps") as? [String] {
+            config.excludeApps = excludedApps
+        }
+        return config
+    }
+
+    // MARK: - アプリ別設定
+
+    /// アプリ別の上書き設定
+    func appOverride(for bundleId: String?) -> AppOverride? {
+        return bundleId.flatMap { appOverrides[$0] }
+    }
+
+    /// アプリ起動後、ウィンドウ配置を始めるまでの待機時間（ミリ秒）
+    /// - app_overrides → app_restore_delays_ms → restore_delay_ms の順に優先
+    func restoreDelayMs(for bundleId: String?) -> UInt64 {
+        return appOverride(for: bundleId)?.restoreDelayMs
+            ?? bundleId.flatMap { appRestoreDelaysMs[$0] }
+            ?? restoreDelayMs
+    }
+
+    /// ウィンドウの対応付け方法
+    func matchingStrategy(for bundleId: String?) -> WindowMatchingStrategy {
+        return appOverride(for: bundleId)?.matching ?? .score
+    }
+
+    /// 未起動の場合に起動しないアプリか
+    func skipsLaunch(_ bundleId: String?) -> Bool {
+        return appOverride(for: bundleId)?.skipLaunch ?? false
+    }
+
+    /// 位置のみ復元するアプリか
+    func restoresPositionOnly(_ bundleId: String?) -> Bool {
+        return appOverride(for: bundleId)?.positionOnly ?? false
+    }
+
+    // MARK: - ディスプレイの別名
+
+    /// ディスプレイの別名（UUID は大文字小文字を区別しない）
+    func displayAlias(for uuid: String) -> String? {
+        if let alias = displayAliases[uuid] { return alias }
+        return displayAliases.first { $0.key.caseInsensitiveCompare(uuid) == .orderedSame }?.value
+    }
+
+    // MARK: - 除外判定
+
+    /// 最小ウィンドウサイズを満たすか
+    func meetsMinimumSize(_ frame: CGRect) -> Bool {
+        return Double(frame.width) >= minWindowSize.width && Double(frame.height) >= minWindowSize.height
+    }
+
+    /// 保存/復元の対象外か（パターンの書式は AppMatcher を参照）
+    /// - include モードでは include_apps に無いアプリも対象外（exclude_apps は両モードで優先）
+    func isExcluded(bundleId: String?, ownerName: String) -> Bool {
+        if AppMatcher.cached(excludeApps).matches(bundleId: bundleId, ownerName: ownerName) { return true }
+        if appFilterMode == .include {
+            return !AppMatcher.cached(includeApps).matches(bundleId: bundleId, ownerName: ownerName)
+        }
+        return false
+    }
+}
+
+/// 未知のキーも含めて列挙するためのキー
+private struct AnyCodingKey: CodingKey {
+    let stringValue: String
+    let intValue: Int? = nil
+
+    init(stringValue: String) {
+        self.stringValue = stringValue
+    }
+
+    init?(intValue: Int) {
+        return nil
+    }
+}
@@ -0,0 +1,72 @@
+import Foundation
+
+/// 環境変数による設定の上書き
+/// - 各項目は `WINDOW_RESTORE_<キーの大文字>`（例: restore_delay_ms → WINDOW_RESTORE_RESTORE_DELAY_MS）
+/// - 優先順位: 環境変数 > 設定ファイル > 既定値（上書きした値はファイルには書き戻さない）
+/// - 値は JSON として解釈し（数値・true/false・配列・オブジェクト）、解釈できない場合は文字列として扱う
+///   リストの項目はカンマ区切りでも指定できる
+extension Config {
+    static let environmentPrefix = "WINDOW_RESTORE_"
+
+    /// 項目に対応する環境変数名
+    static func environmentVariableName(for key: CodingKeys) -> String {
+        return environmentPrefix + key.rawValue.uppercased()
+    }
+
+    /// 環境変数で上書きされている項目
+    static func environmentOverriddenKeys(_ environment: [String: String] = ProcessInfo.processInfo.environment) -> [CodingKeys] {
+        return CodingKeys.allCases.filter { $0 != .version && environment[environmentVariableName(for: $0)] != nil }
+    }
+
+    /// 環境変数による上書きを適用した設定（解釈できない変数はログに出して無視する）
+    func applyingEnvironment(_ environment: [String: String] = ProcessInfo.processInfo.environment) -> Config {
+        let keys = Config.environmentOverriddenKeys(environment)
+        guard !keys.isEmpty,
+              let data = try? JSONEncoder().encode(self),
+              var object = (try? JSONSerialization.jsonObject(with: data)) as? [String: Any] else {
+            return self
+        }
+        var result = self
+        for key in keys {
+            let variable = Config.environmentVariableName(for: key)
+            guard let raw = environment[variable] else { continue }
+            var candidate = object
+            candidate[key.rawValue] = Config.environmentValue(raw, current: object[key.rawValue])
+            do {
+                var config = try JSONDecoder().decode(Config.self, from: JSONSerialization.data(withJSONObject: candidate))
+                config.unknownKeys = unknownKeys
+                config.version = version
+                object = candidate
+                result = config
+            } catch {
+                Log.warning("[config] 環境変数 \(variable) の値を解釈できないため無視します: \(raw)")
+            }
+        }
+        return result
+    }
+
+    // 現在の値の型に合わせて環境変数の文字列を変換する
+    private static func environmentValue(_ raw: String, current: Any?) -> Any {
+        let text = raw.trimmingCharacters(in: .whitespacesAndNewlines)
+        if let current = current, CFGetTypeID(current as CFTypeRef) == CFBooleanGetTypeID() {
+            switch text.lowercased() {
+            case "1", "yes", "on", "true": return true
+            case "0", "no", "off", "false": return false
+            default: return text
+            }
+        }
+        // 文字列の項目（パスなど）はそのまま使う
+        if current is String || current == nil {
+            return text
+        }
+        if let parsed = try? JSONSerialization.jsonObject(with: Data(text.utf8), options: [.fragmentsAllowed]) {
+            return parsed
+        }
+        if current is [Any] {
+            return text.split(separator: ",")
+                .map { $0.trimmingCharacters(in: .whitespaces) }
+                .filter { !$0.isEmpty }
+        }
+        return text
+    }
+}
@@ -0,0 +1,100 @@
+import Foundation
+import TOMLKit
+
+/// config.toml の読み書き補助
+/// - TOML はコメントを書けるため、手で編集する利用者向けに各項目の説明コメント付きで出力する
+/// - 読み込みは他の形式と同じ Config / 検証を通る（SerializationFormat.toml）
+extension Config {
+
+    // 各項目の説明（config.toml の出力時にキーの直前へコメントとして挿入）
+    private static let tomlComments: [CodingKeys: String] = [
+        .autoRestore: "ログイン時に自動復元するか",
+        .displayChangeDetection: "ディスプレイ構成の変化を検知するか",
+        .excludeApps: "保存/復元から除外するアプリ（bundle id またはアプリ名）",
+        .minimizeHiddenWindows: "非表示ウィンドウを最小化扱いにするか",
+        .restoreDelayMs: "アプリ起動後、ウィンドウ配置を始めるまでの待機時間（ミリ秒）",
+        .maxRetryAttempts: "ウィンドウ検出/配置に失敗した場合の最大リトライ回数",
+        .scanIntervalMs: "スキャン間隔（ミリ秒）",
+        .maxMemoryUsageMb: "メモリ使用量上限（MB）",
+        .skipInvisibleWindows: "ユーザーに見えないウィンドウを除外するか",
+        .minWindowAlpha: "この値未満の不透明度のウィンドウを不可視とみなす",
+        .scanCacheTtlMs: "スキャン結果キャッシュの有効期間（ミリ秒。0でキャッシュ無効）",
+        .includeAgentApps: "メニューバー常駐/エージェントアプリのウィンドウも対象にするか",
+        .minWindowSize: "保存対象とする最小ウィンドウサイズ",
+        .restoreFullscreen: "フルスクリーンで保存されたウィンドウをフルスクリーンに戻すか",
+        .maxParallelApps: "復元時に並行処理するアプリ数の上限",
+        .retryInitialDelayMs: "リトライ初回の待機時間（ミリ秒）",
+        .retryBackoffMultiplier: "リトライごとの待機時間の倍率",
+        .retryMaxDelayMs: "リトライ待機時間の上限（ミリ秒）",
+        .launchMissingApps: "復元時に未起動のアプリを起動するか",
+        .restoreTimeoutMs: "復元全体の制限時間（ミリ秒。0で無制限）",
+        .appRestoreDelaysMs: "アプリごとの起動後待機時間（bundle id = ミリ秒）",
+        .animateWindowMoves: "ウィンドウを補間しながら段階的に動かすか",
+        .animationDurationMs: "段階的な移動にかける時間（ミリ秒）",
+        .layoutBackupCount: "レイアウト上書き時に残す過去世代の数（0でバックアップしない）",
+        .storageBackend: "レイアウトの保存先（\"file\" / \"sqlite\"）",
+        .encryptLayouts: "レイアウトを暗号化して保存するか",
+        .layoutsDirectory: "レイアウトの保存先フォルダ（iCloud Drive など）",
+        .layoutFormat: "新規レイアウトを保存する形式（\"json\" / \"yaml\" / \"toml\"）",
+        .autoSnapshotKeep: "自動スナップショットを残す件数（0で無制限）",
+        .autoSnapshotMaxAgeDays: "自動スナップショットの保持日数（0で無制限）",
+        .maxLayouts: "保存しておくレイアウト数の上限（0で無制限）",
+        .appOverrides: "アプリごとの上書き設定（[app_overrides.\"<bundle id>\"]）",
+        .appFilterMode: "対象アプリの指定方法（\"exclude\" / \"include\"）",
+        .includeApps: "app_filter_mode が \"include\" の場合に対象とするアプリ",
+        .defaultLayout: "引数なしの復元で使うレイアウト名",
+        .displayAliases: "ディスプレイの別名（[display_aliases] \"<UUID>\" = \"名前\"）",
+        .autoRestoreOnDisplayChange: "ディスプレイの接続/取り外し時に、同じ配置で保存されたレイアウトを自動で復元するか",
+        .excludeVirtualDisplays: "仮想ディスプレイ（Sidecar、AirPlay、DisplayLink など）をレイアウトの対象外にするか",
+        .notificationDedupSeconds: "同じ内容の通知をまとめる間隔（秒。0でまとめない）",
+        .notificationMaxPerMinute: "1分間に表示する通知の上限（0で無制限）",
+        .notificationsDuringFocus: "集中モード中の重要でない通知の扱い（\"deliver\" / \"suppress\" / \"queue\"）",
+        .logLevel: "出力するログの最低レベル（\"debug\" / \"info\" / \"warning\" / \"error\"）",
+        .logFile: "ログの書き出し先ファイル（未指定の場合は標準出力のみ）",
+        .logMaxSizeKb: "ログファイルをローテーションするサイズ（KB。0でローテーションしない）",
+        .logMaxFiles: "ローテーションで残す過去のログファイル数",
+        .version: "設定ファイルの形式のバージョン（変更しないでください）",
+    ]
+
+    /// 説明コメント付きの TOML
+    func annotatedTOML() throws -> String {
+        let body = try TOMLEncoder().encode(self)
+        var lines = ["# Window Restore 設定ファイル", "# 未指定の項目は既定値が使われます", ""]
+        var commented = Set<CodingKeys>()
+        for line in body.components(separatedBy: "\n") {
+            if let key = Config.tomlKey(of: line), !commented.contains(key), let comment = Config.tomlComments[key] {
+                commented.insert(key)
+                if lines.last?.isEmpty == false { lines.append("") }
+                lines.append("# \(comment)")
+            }
+            lines.append(line)
+        }
+        return lines.joined(separator: "\n")
+    }
+
+    /// 既定値の config.toml を書き出す（既にある場合は何もしない）
+    /// 戻り値: 書き出したファイル
+    @discardableResult
+    static func writeTOMLTemplate() throws -> URL {
+        let url = FileHelper.baseDirectoryURL().appendingPathComponent("config.toml", isDirectory: false)
+        guard !FileManager.default.fileExists(atPath: url.path) else { return url }
+        try FileHelper.ensureParentDirectory(of: url)
+        try Data(Config.load(applyEnvironment: false).annotatedTOML().utf8).write(to: url, options: [.atomic])
+        Log.info("[config] config.toml を作成しました: \(url.path)")
+        return url
+    }
+
+    // "key = value" / "[key]" / "[key.sub]" の行から最上位のキーを取り出す
+    private static func tomlKey(of line: String) -> CodingKeys? {
+        let trimmed = line.trimmingCharacters(in: .whitespaces)
+        let name: Substring
+        if trimmed.hasPrefix("[") {
+            name = trimmed.drop(while: { $0 == "[" }).prefix(while: { $0 != "." && $0 != "]" })
+        } else if let eq = trimmed.firstIndex(of: "=") {
+            name = trimmed[..<eq]
+        } else {
+            return nil
+        }
+        return CodingKeys(rawValue: name.trimmingCharacters(in: .whitespaces))
+    }
+}
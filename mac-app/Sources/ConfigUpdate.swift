@@ -0,0 +1,103 @@
+import Foundation
+
+/// 設定の変更に失敗した理由
+enum ConfigUpdateError: LocalizedError {
+    /// 変更後の設定が検証に通らない（保存していない）
+    case invalid([ConfigValidationError])
+    /// ロックファイルを開けない
+    case lockFailed(path: String)
+
+    var errorDescription: String? {
+        switch self {
+        case .invalid(let errors):
+            return errors.map(\.message).joined(separator: "\n")
+        case .lockFailed(let path):
+            return "設定ファイルをロックできません: \(path)"
+        }
+    }
+}
+
+extension Config {
+    // MARK: - 変更
+
+    private static let updateLock = NSRecursiveLock()
+    // updateLock を保持した状態で読み書きする（入れ子の呼び出しでは flock を取り直さない）
+    private static var fileLockDepth = 0
+
+    /// 保存済みの設定を読み直して変更を適用し、検証してから保存
+    /// - 同じプロセス内の書き込みは直列化し、別プロセスとは `<config>.lock` で排他する（読み直すので他の変更を上書きしない）
+    /// - 読み込み時の修復は行わず、変更を適用した結果を検証する（他の項目の不正な値を黙って既定値に戻して保存しない）
+    /// - 検証エラーがある場合は保存せずに ConfigUpdateError.invalid を投げる
+    @discardableResult
+    static func update(_ body: (inout Config) throws -> Void) throws -> Config {
+        return try withFileLock {
+            // 環境変数の上書きはファイルに書き戻さない
+            var config = Config.load(repair: false, applyEnvironment: false)
+            try body(&config)
+            // 未知の項目は保存されないため変更の妨げにしない
+            let errors = config.validate().filter {
+                if case .unknownField = $0 { return false }
+                return true
+            }
+            guard errors.isEmpty else { throw ConfigUpdateError.invalid(errors) }
+            try config.save()
+            return config
+        }
+    }
+
+    /// 設定ファイルへの書き込みを `<config>.lock` で排他して実行（同じスレッドからの入れ子の呼び出しも可）
+    static func withFileLock<T>(_ body: () throws -> T) throws -> T {
+        updateLock.lock()
+        defer { updateLock.unlock() }
+        var fd: Int32 = -1
+        if fileLockDepth == 0 {
+            let url = FileHelper.configFileURL()
+            try FileHelper.ensureParentDirectory(of: url)
+            let lockPath = url.path + ".lock"
+            fd = open(lockPath, O_CREAT | O_RDWR, 0o644)
+            guard fd >= 0 else { throw ConfigUpdateError.lockFailed(path: lockPath) }
+            flock(fd, LOCK_EX)
+        }
+        fileLockDepth += 1
+        defer {
+            fileLockDepth -= 1
+            if fd >= 0 {
+                flock(fd, LOCK_UN)
+                close(fd)
+            }
+        }
+        return try body()
+    }
+
+    /// 復元待機時間を設定（bundle id を指定した場合はそのアプリの上書き設定）
+    static func setRestoreDelay(_ ms: UInt64, for bundleId: String? = nil) throws {
+        try update { config in
+            if let bundleId = bundleId {
+                config.appOverrides[bundleId, default: AppOverride()].restoreDelayMs = ms
+            } else {
+                config.restoreDelayMs = ms
+            }
+        }
+    }
+
+    /// 除外アプリ（bundle id・アプリ名・パターン）を追加（登録済みなら何もしない）
+    static func addExcludedApp(_ pattern: String) throws {
+        let pattern = pattern.trimmingCharacters(in: .whitespacesAndNewlines)
+        try update { config in
+            if !config.excludeApps.contains(pattern) { config.excludeApps.append(pattern) }
+        }
+    }
+
+    /// 除外アプリを削除
+    static func removeExcludedApp(_ pattern: String) throws {
+        let pattern = pattern.trimmingCharacters(in: .whitespacesAndNewlines)
+        try update { config in
+            config.excludeApps.removeAll { $0 == pattern }
+        }
+    }
+
+    /// 起動時の自動復元を切り替え
+    static func setAutoRestore(_ enabled: Bool) throws {
+        try update { $0.autoRestore = enabled }
+    }
+}
@@ -0,0 +1,211 @@
+import Foundation
+
+/// 設定値の検証エラー（項目単位）
+enum ConfigValidationError: Error, Equatable {
+    /// 許容範囲外の値
+    case outOfRange(field: String, value: String, allowed: String)
+    /// リスト/辞書内の空のエントリ
+    case emptyEntry(field: String)
+    /// 組み合わせが矛盾している（field は既定値に戻す側の項目）
+    case conflict(field: String, with: String, reason: String)
+    /// 未知の項目（このバージョンでは使われない）
+    case unknownField(field: String)
+    /// 解釈できないアプリ指定のパターン（不正な正規表現など）
+    case invalidPattern(field: String, pattern: String)
+
+    /// 対象の項目（config.json のキー）
+    var field: String {
+        switch self {
+        case .outOfRange(let field, _, _), .emptyEntry(let field), .conflict(let field, _, _), .unknownField(let field),
+             .invalidPattern(let field, _):
+            return field
+        }
+    }
+
+    var message: String {
+        switch self {
+        case .outOfRange(let field, let value, let allowed):
+            return "\(field) の値 \(value) は範囲外です（\(allowed)）"
+        case .emptyEntry(let field):
+            return "\(field) に空のエントリがあります"
+        case .conflict(let field, let other, let reason):
+            return "\(field) と \(other) の組み合わせが不正です: \(reason)"
+        case .unknownField(let field):
+            return "\(field) は不明な項目のため無視されます"
+        case .invalidPattern(let field, let pattern):
+            return "\(field) のパターン \(pattern) を解釈できません"
+        }
+    }
+}
+
+extension Config {
+
+    // 遅延・待機時間の上限（ミリ秒）
+    private static let maxDelayMs: UInt64 = 60_000
+
+    /// すべての項目を検証してエラーの一覧を返す（問題が無ければ空）
+    func validate() -> [ConfigValidationError] {
+        var errors: [ConfigValidationError] = []
+        func check<T: Comparable>(_ key: CodingKeys, _ value: T, _ range: ClosedRange<T>) {
+            if !range.contains(value) {
+                errors.append(.outOfRange(field: key.rawValue, value: "\(value)",
+                                          allowed: "\(range.lowerBound)〜\(range.upperBound)"))
+            }
+        }
+
+        check(.restoreDelayMs, restoreDelayMs, 0...Config.maxDelayMs)
+        check(.maxRetryAttempts, maxRetryAttempts, 0...20)
+        check(.scanIntervalMs, scanIntervalMs, 500...3_600_000)
+        check(.maxMemoryUsageMb, maxMemoryUsageMb, 10...4096)
+        check(.minWindowAlpha, minWindowAlpha, 0.0...1.0)
+        check(.scanCacheTtlMs, scanCacheTtlMs, 0...Config.maxDelayMs)
+        if minWindowSize.width < 0 || minWindowSize.height < 0 {
+            errors.append(.outOfRange(field: CodingKeys.minWindowSize.rawValue,
+                                      value: "\(minWindowSize.width)x\(minWindowSize.height)", allowed: "0以上"))
+        }
+        check(.maxParallelApps, maxParallelApps, 1...32)
+        check(.retryInitialDelayMs, retryInitialDelayMs, 0...Config.maxDelayMs)
+        check(.retryBackoffMultiplier, retryBackoffMultiplier, 1.0...10.0)
+        check(.retryMaxDelayMs, retryMaxDelayMs, 0...Config.maxDelayMs)
+        if restoreTimeoutMs != 0 {
+            check(.restoreTimeoutMs, restoreTimeoutMs, 1_000...3_600_000)
+        }
+        check(.animationDurationMs, animationDurationMs, 0...5_000)
+        check(.layoutBackupCount, layoutBackupCount, 0...50)
+        check(.autoSnapshotKeep, autoSnapshotKeep, 0...10_000)
+        check(.autoSnapshotMaxAgeDays, autoSnapshotMaxAgeDays, 0...3_650)
+        check(.maxLayouts, maxLayouts, 0...10_000)
+        check(.logMaxSizeKb, logMaxSizeKb, 0...1_048_576)
+        check(.logMaxFiles, logMaxFiles, 0...50)
+        check(.notificationDedupSeconds, notificationDedupSeconds, 0...86_400)
+        check(.notificationMaxPerMinute, notificationMaxPerMinute, 0...1_000)
+
+        if excludeApps.contains(where: { $0.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty }) {
+            errors.append(.emptyEntry(field: CodingKeys.excludeApps.rawValue))
+        }
+        for pattern in AppMatcher(patterns: excludeApps).invalidPatterns {
+            errors.append(.invalidPattern(field: CodingKeys.excludeApps.rawValue, pattern: pattern))
+        }
+        for pattern in AppMatcher(patterns: includeApps).invalidPatterns {
+            errors.append(.invalidPattern(field: CodingKeys.includeApps.rawValue, pattern: pattern))
+        }
+        if includeApps.contains(where: { $0.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty }) {
+            errors.append(.emptyEntry(field: CodingKeys.includeApps.rawValue))
+        }
+        if appRestoreDelaysMs.keys.contains(where: { $0.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty }) {
+            errors.append(.emptyEntry(field: CodingKeys.appRestoreDelaysMs.rawValue))
+        }
+        if let entry = appRestoreDelaysMs.first(where: { $0.value > Config.maxDelayMs }) {
+            errors.append(.outOfRange(field: CodingKeys.appRestoreDelaysMs.rawValue, value: "\(entry.key)=\(entry.value)",
+                                      allowed: "0〜\(Config.maxDelayMs)"))
+        }
+        if appOverrides.keys.contains(where: { $0.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty }) {
+            errors.append(.emptyEntry(field: CodingKeys.appOverrides.rawValue))
+        }
+        if let entry = appOverrides.first(where: { ($0.value.restoreDelayMs ?? 0) > Config.maxDelayMs }) {
+            errors.append(.outOfRange(field: CodingKeys.appOverrides.rawValue,
+                                      value: "\(entry.key).restore_delay_ms=\(entry.value.restoreDelayMs ?? 0)",
+                                      allowed: "0〜\(Config.maxDelayMs)"))
+        }
+        if let dir = layoutsDirectory, dir.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty {
+            errors.append(.emptyEntry(field: CodingKeys.layoutsDirectory.rawValue))
+        }
+        if displayAliases.contains(where: {
+            $0.key.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty || $0.value.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty
+        }) {
+            errors.append(.emptyEntry(field: CodingKeys.displayAliases.rawValue))
+        }
+        if let file = logFile, file.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty {
+            errors.append(.emptyEntry(field: CodingKeys.logFile.rawValue))
+        }
+
+        if retryMaxDelayMs < retryInitialDelayMs {
+            errors.append(.conflict(field: CodingKeys.retryMaxDelayMs.rawValue, with: CodingKeys.retryInitialDelayMs.rawValue,
+                                    reason: "待機時間の上限が初回の待機時間より短くなっています"))
+        }
+        if maxLayouts > 0, autoSnapshotKeep > maxLayouts {
+            errors.append(.conflict(field: CodingKeys.autoSnapshotKeep.rawValue, with: CodingKeys.maxLayouts.rawValue,
+                                    reason: "自動スナップショットの保持件数がレイアウト数の上限を超えています"))
+        }
+        if appFilterMode == .include, !includeApps.contains(where: { !$0.trimmingCharacters(in: .whitespaces).isEmpty }) {
+            errors.append(.conflict(field: CodingKeys.appFilterMode.rawValue, with: CodingKeys.includeApps.rawValue,
+                                    reason: "include モードですが対象アプリが指定されていません"))
+        }
+        if storageBackend == .sqlite, layoutsDirectory != nil {
+            errors.append(.conflict(field: CodingKeys.layoutsDirectory.rawValue, with: CodingKeys.storageBackend.rawValue,
+                                    reason: "sqlite では保存先フォルダの指定は使われません"))
+        }
+        if version > Config.currentVersion {
+            errors.append(.outOfRange(field: CodingKeys.version.rawValue, value: "\(version)",
+                                      allowed: "\(Config.currentVersion)以下（新しいバージョンのアプリで保存された設定です）"))
+        }
+        errors += unknownKeys.map { .unknownField(field: $0) }
+        return errors
+    }
+
+    /// 検証エラーのある項目を既定値に戻した設定（空のエントリは取り除く）
+    func repaired(_ errors: [ConfigValidationError]? = nil) -> Config {
+        var config = self
+        let d = Config()
+        for error in errors ?? validate() {
+            guard let key = CodingKeys(rawValue: error.field) else { continue }
+            switch key {
+            case .restoreDelayMs: config.restoreDelayMs = d.restoreDelayMs
+            case .maxRetryAttempts: config.maxRetryAttempts = d.maxRetryAttempts
+            case .scanIntervalMs: config.scanIntervalMs = d.scanIntervalMs
+            case .maxMemoryUsageMb: config.maxMemoryUsageMb = d.maxMemoryUsageMb
+            case .minWindowAlpha: config.minWindowAlpha = d.minWindowAlpha
+            case .scanCacheTtlMs: config.scanCacheTtlMs = d.scanCacheTtlMs
+            case .minWindowSize: config.minWindowSize = d.minWindowSize
+            case .maxParallelApps: config.maxParallelApps = d.maxParallelApps
+            case .retryInitialDelayMs: config.retryInitialDelayMs = d.retryInitialDelayMs
+            case .retryBackoffMultiplier: config.retryBackoffMultiplier = d.retryBackoffMultiplier
+            case .retryMaxDelayMs: config.retryMaxDelayMs = max(d.retryMaxDelayMs, config.retryInitialDelayMs)
+            case .restoreTimeoutMs: config.restoreTimeoutMs = d.restoreTimeoutMs
+            case .animationDurationMs: config.animationDurationMs = d.animationDurationMs
+            case .layoutBackupCount: config.layoutBackupCount = d.layoutBackupCount
+            case .autoSnapshotKeep: config.autoSnapshotKeep = min(d.autoSnapshotKeep, config.maxLayouts > 0 ? config.maxLayouts : d.autoSnapshotKeep)
+            case .autoSnapshotMaxAgeDays: config.autoSnapshotMaxAgeDays = d.autoSnapshotMaxAgeDays
+            case .maxLayouts: config.maxLayouts = d.maxLayouts
+            case .excludeApps:
+                let invalid = Set(AppMatcher(patterns: config.excludeApps).invalidPatterns)
+                config.excludeApps = config.excludeApps.filter {
+                    let pattern = $0.trimmingCharacters(in: .whitespacesAndNewlines)
+                    return !pattern.isEmpty && !invalid.contains(pattern)
+                }
+            case .appRestoreDelaysMs:
+                config.appRestoreDelaysMs = config.appRestoreDelaysMs.filter {
+                    !$0.key.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty && $0.value <= Config.maxDelayMs
+                }
+            case .appOverrides:
+                config.appOverrides = config.appOverrides.filter {
+                    !$0.key.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty
+                }.mapValues { entry in
+                    var entry = entry
+                    if (entry.restoreDelayMs ?? 0) > Config.maxDelayMs { entry.restoreDelayMs = nil }
+                    return entry
+                }
+            case .includeApps:
+                let invalid = Set(AppMatcher(patterns: config.includeApps).invalidPatterns)
+                config.includeApps = config.includeApps.filter {
+                    let pattern = $0.trimmingCharacters(in: .whitespacesAndNewlines)
+                    return !pattern.isEmpty && !invalid.contains(pattern)
+                }
+            case .appFilterMode: config.appFilterMode = d.appFilterMode
+            case .layoutsDirectory: config.layoutsDirectory = d.layoutsDirectory
+            case .displayAliases:
+                config.displayAliases = config.displayAliases.filter {
+                    !$0.key.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty
+                        && !$0.value.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty
+                }
+            case .logFile: config.logFile = d.logFile
+            case .logMaxSizeKb: config.logMaxSizeKb = d.logMaxSizeKb
+            case .logMaxFiles: config.logMaxFiles = d.logMaxFiles
+            case .notificationDedupSeconds: config.notificationDedupSeconds = d.notificationDedupSeconds
+            case .notificationMaxPerMinute: config.notificationMaxPerMinute = d.notificationMaxPerMinute
+            default: break
+            }
+        }
+        return config
+    }
+}
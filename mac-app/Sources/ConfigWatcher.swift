@@ -0,0 +1,94 @@
+import Foundation
+
+/// 設定ファイルの変更監視（kqueue / DispatchSource）
+/// - 保存ツールによってはファイルを置き換える（アトミック書き込み）ため、データディレクトリとファイルの両方を監視する
+/// - 内容が実際に変わった場合のみ、読み込み直した設定をメインスレッドでハンドラに通知する
+final class ConfigWatcher {
+    typealias Handler = (Config) -> Void
+
+    private let handler: Handler
+    private let queue = DispatchQueue(label: "window_restore.config-watcher")
+    private var directorySource: DispatchSourceFileSystemObject?
+    private var fileSource: DispatchSourceFileSystemObject?
+    private var lastData: Data?
+    private var pendingReload: DispatchWorkItem?
+
+    // 連続した書き込みをまとめるための待ち時間
+    private static let debounceMs = 200
+
+    init(handler: @escaping Handler) {
+        self.handler = handler
+    }
+
+    deinit {
+        stop()
+    }
+
+    // MARK: - 開始/停止
+
+    func start() {
+        queue.sync {
+            guard directorySource == nil else { return }
+            lastData = try? Data(contentsOf: FileHelper.configFileURL())
+            try? FileHelper.ensureDirectories()
+            directorySource = makeSource(path: FileHelper.baseDirectoryURL().path, events: .write)
+            watchConfigFile()
+        }
+        Log.info("[config] 設定ファイルの監視を開始しました")
+    }
+
+    func stop() {
+        queue.sync {
+            pendingReload?.cancel()
+            pendingReload = nil
+            directorySource?.cancel()
+            directorySource = nil
+            fileSource?.cancel()
+            fileSource = nil
+        }
+    }
+
+    // MARK: - 監視
+
+    // ファイルが置き換えられると古いディスクリプタは無効になるため、イベントごとに開き直す
+    private func watchConfigFile() {
+        fileSource?.cancel()
+        fileSource = makeSource(path: FileHelper.configFileURL().path, events: [.write, .extend, .delete, .rename])
+    }
+
+    private func makeSource(path: String, events: DispatchSource.FileSystemEvent) -> DispatchSourceFileSystemObject? {
+        let fd = open(path, O_EVTONLY)
+        guard fd >= 0 else { return nil }
+        let source = DispatchSource.makeFileSystemObjectSource(fileDescriptor: fd, eventMask: events, queue: queue)
+        source.setEventHandler { [weak self] in
+            self?.scheduleReload()
+        }
+        source.setCancelHandler {
+            close(fd)
+        }
+        source.resume()
+        return source
+    }
+
+    private func scheduleReload() {
+        pendingReload?.cancel()
+        let item = DispatchWorkItem { [weak self] in
+            self?.reloadIfChanged()
+        }
+        pendingReload = item
+        queue.asyncAfter(deadline: .now() + .milliseconds(ConfigWatcher.debounceMs), execute: item)
+    }
+
+    private func reloadIfChanged() {
+        watchConfigFile()
+        let data = try? Data(contentsOf: FileHelper.configFileURL())
+        guard data != lastData else { return }
+        lastData = data
+        Config.invalidateCache()
+        let config = Config.load()
+        Log.info("[config] 設定ファイルの変更を検知しました")
+        DispatchQueue.main.async { [handler] in
+            handler(config)
+        }
+    }
+}
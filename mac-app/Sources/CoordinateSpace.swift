@@ -0,0 +1,62 @@
+import Cocoa
+import CoreGraphics
+
+/// 座標系の変換
+/// - CG グローバル座標: メインディスプレイ左上原点、Y軸下向き（CGWindowList、CGDisplayBounds、AX の位置/サイズ）
+/// - AppKit 座標: メインディスプレイ左下原点、Y軸上向き（NSScreen、NSWindow）
+/// - ディスプレイ内座標: ディスプレイの左上原点、Y軸下向き（CG グローバル座標からディスプレイの原点を引いたもの）
+/// - 比率: 基準となる矩形の大きさを 1 とした値（テンプレートやプレビュー）
+/// スキャン/復元の座標計算はこれらを通して行い、保存する値は常に CG グローバル座標とする
+extension DisplayManager {
+
+    // AppKit 座標の基準（メインディスプレイ = NSScreen.screens の先頭）の高さ
+    private static var appKitReferenceHeight: CGFloat {
+        return CGDisplayBounds(CGMainDisplayID()).height
+    }
+
+    /// CG グローバル座標 → AppKit 座標
+    static func cgToAppKit(_ rect: CGRect) -> CGRect {
+        return CGRect(x: rect.minX, y: appKitReferenceHeight - rect.maxY, width: rect.width, height: rect.height)
+    }
+
+    /// AppKit 座標 → CG グローバル座標
+    static func appKitToCG(_ rect: CGRect) -> CGRect {
+        // 上下反転は対称なので同じ式になる
+        return CGRect(x: rect.minX, y: appKitReferenceHeight - rect.maxY, width: rect.width, height: rect.height)
+    }
+
+    /// CG グローバル座標 → ディスプレイ内座標
+    static func globalToDisplayLocal(_ rect: CGRect, display: CGRect) -> CGRect {
+        return rect.offsetBy(dx: -display.minX, dy: -display.minY)
+    }
+
+    /// ディスプレイ内座標 → CG グローバル座標
+    static func displayLocalToGlobal(_ rect: CGRect, display: CGRect) -> CGRect {
+        return rect.offsetBy(dx: display.minX, dy: display.minY)
+    }
+
+    /// 矩形を基準の矩形に対する比率で表す（基準の大きさが 0 の場合は nil）
+    static func normalized(_ rect: CGRect, in reference: CGRect) -> CGRect? {
+        guard reference.width > 0, reference.height > 0 else { return nil }
+        let local = globalToDisplayLocal(rect, display: reference)
+        return CGRect(x: local.minX / reference.width, y: local.minY / reference.height,
+                      width: local.width / reference.width, height: local.height / reference.height)
+    }
+
+    /// 比率で表した矩形を基準の矩形に当てはめる（ピクセル単位に丸める）
+    static func denormalized(_ rect: CGRect, in reference: CGRect) -> CGRect {
+        let local = CGRect(x: rect.minX * reference.width, y: rect.minY * reference.height,
+                           width: rect.width * reference.width, height: rect.height * reference.height)
+        let global = displayLocalToGlobal(local, display: reference)
+        return CGRect(x: global.minX.rounded(), y: global.minY.rounded(),
+                      width: global.width.rounded(), height: global.height.rounded())
+    }
+}
+
+extension CGRect {
+    /// 位置とサイズの差がすべて tolerance（px）以内か（AX と CGWindowList の丸め誤差を吸収したフレームの比較）
+    func isApproximatelyEqual(to other: CGRect, tolerance: CGFloat) -> Bool {
+        return abs(minX - other.minX) <= tolerance && abs(minY - other.minY) <= tolerance
+            && abs(width - other.width) <= tolerance && abs(height - other.height) <= tolerance
+    }
+}
@@ -0,0 +1,83 @@
+import Foundation
+
+/// ディスプレイの接続/取り外し時の自動復元
+/// - 構成変更のイベントが落ち着いてから配置の指紋（DisplayManager.arrangementFingerprint）を比べ、
+///   変わっていれば同じ配置で保存されたレイアウト（WindowManager.layoutsMatchingCurrentDisplays の先頭）を復元する
+/// - Config.autoRestoreOnDisplayChange と displayChangeDetection が有効な場合のみ動作する（変更のたびに読み直す）
+final class DisplayAutoRestorer {
+    private let queue = DispatchQueue(label: "window_restore.display-auto-restore")
+    private var watcher: DisplayWatcher?
+    private var pending: DispatchWorkItem?
+    private var lastFingerprint: String?
+
+    // 接続直後はイベントが続き、ウィンドウも再配置中のため少し待つ
+    private static let settleDelayMs = 2_000
+
+    deinit {
+        stop()
+    }
+
+    // MARK: - 開始/停止
+
+    func start() {
+        guard watcher == nil else { return }
+        queue.sync { lastFingerprint = DisplayManager.arrangementFingerprint() }
+        watcher = WindowManager.shared.watchDisplays { [weak self] event in
+            switch event {
+            case .connected, .disconnected, .resolutionChanged, .moved, .mirroringChanged:
+                self?.scheduleCheck()
+            case .mainChanged:
+                break
+            }
+        }
+        Log.info("[watch] ディスプレイ接続時の自動復元を開始しました")
+    }
+
+    func stop() {
+        watcher?.stop()
+        watcher = nil
+        queue.sync {
+            pending?.cancel()
+            pending = nil
+        }
+    }
+
+    // MARK: - 復元
+
+    private func scheduleCheck() {
+        queue.async {
+            self.pending?.cancel()
+            let item = DispatchWorkItem { [weak self] in self?.checkArrangement() }
+            self.pending = item
+            self.queue.asyncAfter(deadline: .now() + .milliseconds(DisplayAutoRestorer.settleDelayMs), execute: item)
+        }
+    }
+
+    // queue 上で呼ぶ
+    private func checkArrangement() {
+        let config = Config.load()
+        guard config.autoRestoreOnDisplayChange, config.displayChangeDetection else { return }
+        let fingerprint = DisplayManager.arrangementFingerprint()
+        guard fingerprint != lastFingerprint else { return }
+        lastFingerprint = fingerprint
+
+        let manager = WindowManager.shared
+        guard let name = manager.layoutsMatchingCurrentDisplays().first else {
+            Log.info("[restore] 新しいディスプレイ構成に一致するレイアウトがありません")
+            return
+        }
+        guard manager.hasAccessibilityPermission() else {
+            Log.warning("[restore] アクセシビリティ権限が無いため自動復元できません: \(name)")
+            return
+        }
+        Log.info("[restore] ディスプレイ構成の変化に合わせて復元します: \(name)")
+        do {
+            let report = try manager.restoreWindows(name: name)
+            NotificationManager.post(title: "ディスプレイ構成に合わせて復元しました",
+                                     body: "レイアウト「\(name)」を復元しました（\(report.restored) ウィンドウ）")
+        } catch {
+            Log.warning("[restore] 自動復元に失敗 \(name): \(error.localizedDescription)")
+            NotificationManager.warn(title: "自動復元エラー", body: error.localizedDescription)
+        }
+    }
+}
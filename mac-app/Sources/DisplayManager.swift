@@ -0,0 +1,324 @@
+import Cocoa
+import Foundation
+import CoreGraphics
+import CryptoKit
+
+/// ディスプレイ1台分の情報（レイアウト保存時のモニター構成の記録用）
+struct DisplayInfo: Codable, Equatable {
+    let uuid: String
+    /// CG グローバル座標でのフレーム（解像度と配置）
+    let bounds: CGRect
+    let isMain: Bool
+    /// 表示名（例: "LG HDR 4K"。旧データは nil）
+    var name: String? = nil
+    /// Retina などのスケール（ポイントあたりのピクセル数。旧データは nil）
+    var scaleFactor: Double? = nil
+    /// 設定（display_aliases）で付けた別名（保存時点のもの）
+    var alias: String? = nil
+    /// メニューバーと Dock を除いた表示領域（CG グローバル座標。旧データは nil）
+    var visibleFrame: CGRect? = nil
+    /// Sidecar・AirPlay・DisplayLink などの仮想ディスプレイか（旧データは nil）
+    var isVirtual: Bool? = nil
+
+    /// 表示用の名前（現在の設定の別名 → 保存時の別名 → 表示名 → UUID の順）
+    func label(config: Config = Config.load()) -> String {
+        return config.displayAlias(for: uuid) ?? alias ?? name ?? uuid
+    }
+}
+
+/// 保存時と現在のディスプレイ構成の一致度
+enum DisplayMatch: Equatable {
+    /// 同じモニターが同じ配置・解像度で接続されている
+    case identical
+    /// 同じモニターだが配置または解像度が異なる（座標は換算して復元できる）
+    case rearranged
+    /// 保存時のモニターの一部が接続されていない
+    case partial(missing: [String])
+    /// 保存時のモニターが1台も接続されていない
+    case different
+}
+
+/// ディスプレイ情報の取得ヘルパー
+/// - 座標はすべて CG グローバル座標（メインディスプレイ左上原点、Y軸下向き）で扱う
+enum DisplayManager {
+
+    // 取得可能な最大ディスプレイ数
+    private static let maxDisplays: UInt32 = 32
+
+    /// アクティブなディスプレイIDの一覧
+    /// - ミラーリング中のディスプレイは1台の論理ディスプレイとして扱い、ミラー元（プライマリ）のみを返す
+    ///   （同じ座標を共有するため、両方を数えるとスキャン/復元で重複する）
+    static func activeDisplayIDs() -> [CGDirectDisplayID] {
+        return allActiveDisplayIDs().filter { CGDisplayMirrorsDisplay($0) == kCGNullDirectDisplay }
+    }
+
+    /// ミラー先も含めたアクティブなディスプレイIDの一覧
+    private static func allActiveDisplayIDs() -> [CGDirectDisplayID] {
+        var ids = [CGDirectDisplayID](repeating: 0, count: Int(maxDisplays))
+        var count: UInt32 = 0
+        guard CGGetActiveDisplayList(maxDisplays, &ids, &count) == .success else { return [] }
+        return Array(ids.prefix(Int(count)))
+    }
+
+    /// ミラーリングされているディスプレイの組（プライマリ → ミラー先）
+    static func mirrorSets() -> [CGDirectDisplayID: [CGDirectDisplayID]] {
+        var sets: [CGDirectDisplayID: [CGDirectDisplayID]] = [:]
+        for id in allActiveDisplayIDs() where CGDisplayIsInMirrorSet(id) != 0 {
+            let primary = CGDisplayPrimaryDisplay(id)
+            if primary != id {
+                sets[primary, default: []].append(id)
+            }
+        }
+        return sets
+    }
+
+    /// ディスプレイIDからUUID文字列を解決
+    /// - CGDisplayCreateUUIDFromDisplayID の UUID は同じモニターなら再接続・再起動後も変わらない
+    /// - 取得できない場合はベンダー/モデル/シリアル番号から作った識別子（"display-<vendor>-<model>-<serial>"）
+    ///   ディスプレイID は接続のたびに変わりうるため、識別子には使わない
+    static func uuidString(for displayId: CGDirectDisplayID) -> String {
+        if let uuidRef = CGDisplayCreateUUIDFromDisplayID(displayId)?.takeRetainedValue(),
+           let cfStr = CFUUIDCreateString(kCFAllocatorDefault, uuidRef) {
+            return cfStr as String
+        }
+        let vendor = String(CGDisplayVendorNumber(displayId), radix: 16)
+        let model = String(CGDisplayModelNumber(displayId), radix: 16)
+        let serial = String(CGDisplaySerialNumber(displayId), radix: 16)
+        return "display-\(vendor)-\(model)-\(serial)"
+    }
+
+    /// フレームが最も大きく重なるディスプレイのIDを返す
+    /// - どのディスプレイとも重ならない場合は中心点に最も近いディスプレイ
+    static func displayID(for frame: CGRect) -> CGDirectDisplayID? {
+        let ids = activeDisplayIDs()
+        var best: (id: CGDirectDisplayID, area: CGFloat)?
+        for id in ids {
+            let intersection = CGDisplayBounds(id).intersection(frame)
+            guard !intersection.isNull else { continue }
+            let area = intersection.width * intersection.height
+            if area > (best?.area ?? 0) {
+                best = (id, area)
+            }
+        }
+        if let best = best { return best.id }
+
+        let center = CGPoint(x: frame.midX, y: frame.midY)
+        return ids.min { a, b in
+            distance(from: center, to: CGDisplayBounds(a)) < distance(from: center, to: CGDisplayBounds(b))
+        }
+    }
+
+    /// フレームが主に属するディスプレイのUUID
+    static func displayUUID(for frame: CGRect) -> String? {
+        return displayID(for: frame).map { uuidString(for: $0) }
+    }
+
+    /// UUID に対応する現在アクティブなディスプレイ
+    /// - ミラー先になっているディスプレイの場合はミラー元（プライマリ）を返す
+    /// - 旧バージョンで UUID の代わりに記録したディスプレイID（数字のみ）も受け付ける
+    static func displayID(forUUID uuid: String) -> CGDirectDisplayID? {
+        let ids = activeDisplayIDs()
+        if let match = allActiveDisplayIDs().first(where: { uuidString(for: $0).caseInsensitiveCompare(uuid) == .orderedSame }) {
+            return CGDisplayPrimaryDisplay(match)
+        }
+        if let legacyId = CGDirectDisplayID(uuid), ids.contains(legacyId) {
+            return legacyId
+        }
+        return nil
+    }
+
+    /// 保存時のフレーム（CG グローバル座標）を現在のディスプレイ配置に合わせて変換
+    /// - 保存時のディスプレイが接続されていれば、そのディスプレイ内の相対位置を保ったまま現在の位置へ移す
+    /// - 接続されていなければ、位置と大きさが最も近い現在のディスプレイ（nearestDisplayID）へ同様に移す
+    /// - 解像度やスケーリングが変わっている場合は位置とサイズを比率で換算する（rescale）
+    /// - 保存時のディスプレイ情報が無い（旧データ）場合は元のフレーム
+    /// - 対象外の仮想ディスプレイ（exclude_virtual_displays）へは配置しない
+    static func mapFrame(_ frame: CGRect, fromDisplay uuid: String?, savedDisplayBounds: CGRect?) -> CGRect {
+        guard let saved = savedDisplayBounds, saved.width > 0, saved.height > 0 else {
+            return frame
+        }
+        let candidates = layoutDisplayIDs()
+        let displayId: CGDirectDisplayID
+        if let uuid = uuid, let id = displayID(forUUID: uuid), candidates.contains(id) {
+            displayId = id
+        } else if let nearest = nearestDisplayID(to: saved, among: candidates) {
+            displayId = nearest
+        } else {
+            return frame
+        }
+        let current = CGDisplayBounds(displayId)
+        if current == saved { return frame }
+        return rescale(frame, from: saved, to: current)
+    }
+
+    /// ディスプレイのフレームが saved から current へ変わった場合のウィンドウのフレームを換算
+    /// - 大きさが同じ（配置のみ変わった）場合は平行移動のみ
+    /// - 大きさが変わった場合は、ディスプレイ内の相対位置とディスプレイに対するサイズの比率を保つ
+    static func rescale(_ frame: CGRect, from saved: CGRect, to current: CGRect) -> CGRect {
+        guard saved.width > 0, saved.height > 0 else { return frame }
+        if saved.size == current.size {
+            return displayLocalToGlobal(globalToDisplayLocal(frame, display: saved), display: current)
+        }
+        guard let relative = normalized(frame, in: saved) else { return frame }
+        return denormalized(relative, in: current)
+    }
+
+    /// 保存時のディスプレイ（フレーム）に最も近い現在のディスプレイ
+    /// - 中心間の距離（保存時のディスプレイの大きさで正規化）と面積比の差の合計が最小のもの
+    /// - 同じ位置・大きさのディスプレイがあればそれを返す
+    static func nearestDisplayID(to saved: CGRect, among ids: [CGDirectDisplayID] = activeDisplayIDs()) -> CGDirectDisplayID? {
+        guard saved.width > 0, saved.height > 0 else { return nil }
+        let scale = max(saved.width, saved.height)
+        func score(_ id: CGDirectDisplayID) -> CGFloat {
+            let b = CGDisplayBounds(id)
+            guard b.width > 0, b.height > 0 else { return .greatestFiniteMagnitude }
+            let dx = b.midX - saved.midX
+            let dy = b.midY - saved.midY
+            let positionScore = (dx * dx + dy * dy).squareRoot() / scale
+            let sizeScore = abs(log((b.width * b.height) / (saved.width * saved.height)))
+            return positionScore + sizeScore
+        }
+        return ids.min { score($0) < score($1) }
+    }
+
+    /// フレームがいずれかのアクティブなディスプレイと重なっているか
+    static func isOnAnyDisplay(_ frame: CGRect) -> Bool {
+        return activeDisplayIDs().contains { !CGDisplayBounds($0).intersection(frame).isNull }
+    }
+
+    /// ディスプレイIDに対応する NSScreen
+    static func screen(for displayId: CGDirectDisplayID) -> NSScreen? {
+        let key = NSDeviceDescriptionKey("NSScreenNumber")
+        return NSScreen.screens.first {
+            ($0.deviceDescription[key] as? NSNumber)?.uint32Value == displayId
+        }
+    }
+
+    /// ディスプレイの表示名（NSScreen が無い場合は内蔵/外部の区別のみ）
+    static func displayName(for displayId: CGDirectDisplayID) -> String {
+        if let name = screen(for: displayId)?.localizedName, !name.isEmpty {
+            return name
+        }
+        return CGDisplayIsBuiltin(displayId) != 0 ? "内蔵ディスプレイ" : "外部ディスプレイ \(displayId)"
+    }
+
+    /// ディスプレイのスケール（NSScreen が無い場合は表示モードのピクセル数とポイント数の比）
+    static func scaleFactor(for displayId: CGDirectDisplayID) -> Double {
+        if let screen = screen(for: displayId) {
+            return Double(screen.backingScaleFactor)
+        }
+        guard let mode = CGDisplayCopyDisplayMode(displayId), mode.width > 0 else { return 1.0 }
+        return Double(mode.pixelWidth) / Double(mode.width)
+    }
+
+    /// ディスプレイの表示領域（メニューバー/Dockを除く）を CG グローバル座標で返す
+    /// - 対応する NSScreen が無い場合はディスプレイ全体
+    static func visibleFrame(for displayId: CGDirectDisplayID) -> CGRect {
+        guard let screen = screen(for: displayId) else {
+            return CGDisplayBounds(displayId)
+        }
+        // AppKit 座標（メインディスプレイ左下原点、Y軸上向き）から変換
+        return appKitToCG(screen.visibleFrame)
+    }
+
+    // 配置先として十分に見えているとみなす最小の重なり（px）
+    private static let minVisibleExtent: CGFloat = 100
+
+    /// フレームが表示領域に十分見える位置になるよう補正
+    /// - 最も重なる（無ければ最も近い）ディスプレイの表示領域（メニューバーと Dock を除く）を基準にする
+    /// - メニューバー/Dock の下に潜らず、タイトルバーが表示領域内にあり、一定以上重なっていればそのまま返す
+    ///   （ディスプレイの外側へはみ出しているだけの場合は許容する）
+    /// - それ以外は表示領域に収まるようサイズを縮め、位置を内側へ寄せる
+    static func clampToVisibleArea(_ frame: CGRect) -> CGRect {
+        guard let displayId = displayID(for: frame) else { return frame }
+        let visible = visibleFrame(for: displayId)
+        let overlap = visible.intersection(frame)
+        let onDisplay = CGDisplayBounds(displayId).intersection(frame)
+        // 座標の丸め誤差は許容する
+        let underMenuBarOrDock = !onDisplay.isNull && !visible.insetBy(dx: -1, dy: -1).contains(onDisplay)
+        let titleBarVisible = frame.minY >= visible.minY && frame.minY < visible.maxY
+        if !overlap.isNull, titleBarVisible, !underMenuBarOrDock,
+           overlap.width >= min(minVisibleExtent, frame.width), overlap.height >= min(minVisibleExtent, frame.height) {
+            return frame
+        }
+        let width = min(frame.width, visible.width)
+        let height = min(frame.height, visible.height)
+        let x = min(max(frame.minX, visible.minX), visible.maxX - width)
+        let y = min(max(frame.minY, visible.minY), visible.maxY - height)
+        return CGRect(x: x, y: y, width: width, height: height)
+    }
+
+    /// ディスプレイ配置の指紋（UUID・解像度・相対位置の組をソートして SHA-256 化）
+    /// - 同じモニター構成・配置・解像度なら同じ値になる
+    /// - 位置は全ディスプレイを囲む矩形の左上からの相対値のため、メインディスプレイを切り替えても変わらない
+    /// - レイアウトの記録済みディスプレイ構成（Layout.displays）からも計算できる
+    static func arrangementFingerprint(_ displays: [DisplayInfo] = currentDisplays()) -> String {
+        let originX = displays.map(\.bounds.minX).min() ?? 0
+        let originY = displays.map(\.bounds.minY).min() ?? 0
+        let components = displays.map { d -> String in
+            let b = d.bounds
+            return "\(d.uuid.uppercased())@\(Int(b.minX - originX)),\(Int(b.minY - originY)),\(Int(b.width))x\(Int(b.height))"
+        }.sorted()
+        let digest = SHA256.hash(data: Data(components.joined(separator: ";").utf8))
+        return digest.map { String(format: "%02x", $0) }.joined()
+    }
+
+    /// 現在接続されているディスプレイの一覧（名前・フレーム・メインかどうか・スケール・別名）
+    /// - exclude_virtual_displays が有効な場合は仮想ディスプレイを含めない
+    static func currentDisplays() -> [DisplayInfo] {
+        let config = Config.load()
+        return layoutDisplayIDs(config: config).map { displayInfo(for: $0, config: config) }
+    }
+
+    /// ディスプレイ1台分の情報
+    static func displayInfo(for displayId: CGDirectDisplayID, config: Config = Config.load()) -> DisplayInfo {
+        let uuid = uuidString(for: displayId)
+        return DisplayInfo(uuid: uuid, bounds: CGDisplayBounds(displayId),
+                           isMain: displayId == CGMainDisplayID(),
+                           name: displayName(for: displayId), scaleFactor: scaleFactor(for: displayId),
+                           alias: config.displayAlias(for: uuid), visibleFrame: visibleFrame(for: displayId),
+                           isVirtual: isVirtualDisplay(displayId))
+    }
+
+    // 仮想ディスプレイの表示名に含まれる語（小文字）
+    private static let virtualDisplayKeywords = ["sidecar", "airplay", "displaylink", "virtual", "dummy", "ipad"]
+
+    /// 仮想ディスプレイ（Sidecar、AirPlay、DisplayLink、仮想ディスプレイ作成ツールなど）か
+    /// - 公開 API では判別できないため、表示名と、ベンダー/モデル番号が無いこと（EDID を持たない）から推定する
+    static func isVirtualDisplay(_ displayId: CGDirectDisplayID) -> Bool {
+        if CGDisplayIsBuiltin(displayId) != 0 { return false }
+        let name = displayName(for: displayId).lowercased()
+        if virtualDisplayKeywords.contains(where: { name.contains($0) }) { return true }
+        return CGDisplayVendorNumber(displayId) == 0 && CGDisplayModelNumber(displayId) == 0
+    }
+
+    /// レイアウトの保存/復元の対象とするディスプレイ（exclude_virtual_displays が有効なら仮想ディスプレイを除く）
+    static func layoutDisplayIDs(config: Config = Config.load()) -> [CGDirectDisplayID] {
+        let ids = activeDisplayIDs()
+        return config.excludeVirtualDisplays ? ids.filter { !isVirtualDisplay($0) } : ids
+    }
+
+    /// UUID のディスプレイの表示用の名前（別名 → 接続中なら表示名 → UUID）
+    static func label(forUUID uuid: String, config: Config = Config.load()) -> String {
+        if let alias = config.displayAlias(for: uuid) { return alias }
+        if let id = displayID(forUUID: uuid) { return displayName(for: id) }
+        return uuid
+    }
+
+    /// 保存時のディスプレイ構成と比較
+    static func compare(saved: [DisplayInfo], current: [DisplayInfo] = currentDisplays()) -> DisplayMatch {
+        let currentByUUID = Dictionary(current.map { ($0.uuid, $0) }, uniquingKeysWith: { a, _ in a })
+        let missing = saved.map(\.uuid).filter { currentByUUID[$0] == nil }
+        if !saved.isEmpty && missing.count == saved.count { return .different }
+        if !missing.isEmpty { return .partial(missing: missing) }
+        let unchanged = saved.count == current.count && saved.allSatisfy { currentByUUID[$0.uuid]?.bounds == $0.bounds }
+        return unchanged ? .identical : .rearranged
+    }
+
+    // 点から矩形までの距離（矩形内なら0）
+    private static func distance(from point: CGPoint, to rect: CGRect) -> CGFloat {
+        let dx = max(rect.minX - point.x, 0, point.x - rect.maxX)
+        let dy = max(rect.minY - point.y, 0, point.y - rect.maxY)
+        return (dx * dx + dy * dy).squareRoot()
+    }
+}
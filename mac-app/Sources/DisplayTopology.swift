@@ -0,0 +1,78 @@
+import Foundation
+import CoreGraphics
+
+/// UUID に依存しないディスプレイの指定（モニターを交換してもレイアウトを使い回せるようにする）
+enum DisplayPosition: String, Codable, CaseIterable {
+    case main
+    case leftmost
+    case rightmost
+    case topmost
+    case bottommost
+}
+
+/// ディスプレイ構成の中での1台の位置関係
+struct DisplayPlacement: Equatable {
+    let uuid: String
+    /// 左から数えた順位（0 = 最も左）
+    let horizontalIndex: Int
+    /// 上から数えた順位（0 = 最も上）
+    let verticalIndex: Int
+    let isMain: Bool
+    /// 当てはまる位置（例: メインかつ最も左なら [.main, .leftmost]）
+    let positions: [DisplayPosition]
+}
+
+/// ディスプレイの位置関係の問い合わせ
+/// - 左右/上下の判定はディスプレイの中心座標で行い、近いものから順に返す
+extension DisplayManager {
+
+    /// 指定ディスプレイより左にあるディスプレイ（近い順）
+    static func displays(leftOf uuid: String, in displays: [DisplayInfo] = currentDisplays()) -> [DisplayInfo] {
+        guard let target = find(uuid, in: displays) else { return [] }
+        return displays.filter { $0.bounds.midX < target.bounds.midX }.sorted { $0.bounds.midX > $1.bounds.midX }
+    }
+
+    /// 指定ディスプレイより右にあるディスプレイ（近い順）
+    static func displays(rightOf uuid: String, in displays: [DisplayInfo] = currentDisplays()) -> [DisplayInfo] {
+        guard let target = find(uuid, in: displays) else { return [] }
+        return displays.filter { $0.bounds.midX > target.bounds.midX }.sorted { $0.bounds.midX < $1.bounds.midX }
+    }
+
+    /// 指定ディスプレイより上にあるディスプレイ（近い順。CG 座標は Y 軸下向き）
+    static func displays(above uuid: String, in displays: [DisplayInfo] = currentDisplays()) -> [DisplayInfo] {
+        guard let target = find(uuid, in: displays) else { return [] }
+        return displays.filter { $0.bounds.midY < target.bounds.midY }.sorted { $0.bounds.midY > $1.bounds.midY }
+    }
+
+    /// 指定ディスプレイより下にあるディスプレイ（近い順）
+    static func displays(below uuid: String, in displays: [DisplayInfo] = currentDisplays()) -> [DisplayInfo] {
+        guard let target = find(uuid, in: displays) else { return [] }
+        return displays.filter { $0.bounds.midY > target.bounds.midY }.sorted { $0.bounds.midY < $1.bounds.midY }
+    }
+
+    /// 位置で指定したディスプレイ（該当なしは nil）
+    static func display(at position: DisplayPosition, in displays: [DisplayInfo] = currentDisplays()) -> DisplayInfo? {
+        switch position {
+        case .main: return displays.first { $0.isMain } ?? displays.first
+        case .leftmost: return displays.min { $0.bounds.midX < $1.bounds.midX }
+        case .rightmost: return displays.max { $0.bounds.midX < $1.bounds.midX }
+        case .topmost: return displays.min { $0.bounds.midY < $1.bounds.midY }
+        case .bottommost: return displays.max { $0.bounds.midY < $1.bounds.midY }
+        }
+    }
+
+    /// 指定ディスプレイの位置関係（該当なしは nil）
+    static func placement(of uuid: String, in displays: [DisplayInfo] = currentDisplays()) -> DisplayPlacement? {
+        guard let target = find(uuid, in: displays) else { return nil }
+        let positions = DisplayPosition.allCases.filter { display(at: $0, in: displays)?.uuid == target.uuid }
+        return DisplayPlacement(uuid: target.uuid,
+                                horizontalIndex: self.displays(leftOf: target.uuid, in: displays).count,
+                                verticalIndex: self.displays(above: target.uuid, in: displays).count,
+                                isMain: target.isMain,
+                                positions: positions)
+    }
+
+    private static func find(_ uuid: String, in displays: [DisplayInfo]) -> DisplayInfo? {
+        return displays.first { $0.uuid.caseInsensitiveCompare(uuid) == .orderedSame }
+    }
+}
@@ -0,0 +1,92 @@
+import Foundation
+import CoreGraphics
+
+/// ディスプレイ構成の変化イベント
+enum DisplayEvent: Equatable {
+    case connected(DisplayInfo)
+    /// 取り外されたディスプレイ（接続中に記録した UUID）
+    case disconnected(uuid: String)
+    case resolutionChanged(DisplayInfo)
+    case moved(DisplayInfo)
+    case mainChanged(DisplayInfo)
+    case mirroringChanged(DisplayInfo)
+}
+
+/// CGDisplayRegisterReconfigurationCallback によるディスプレイ構成の監視
+/// - ドックへの接続/取り外し、解像度・配置・ミラーリングの変更をディスプレイごとのイベントとして通知する
+/// - イベントはメインスレッドでハンドラに通知される（構成変更の開始通知は無視し、完了後の通知のみ扱う）
+final class DisplayWatcher {
+    typealias Handler = (DisplayEvent) -> Void
+
+    private let handler: Handler
+    private var isRunning = false
+    // 取り外し時は UUID を解決できないことがあるため、接続中のものを覚えておく
+    private var knownUUIDs: [CGDirectDisplayID: String] = [:]
+
+    init(handler: @escaping Handler) {
+        self.handler = handler
+    }
+
+    deinit {
+        stop()
+    }
+
+    // MARK: - 開始/停止
+
+    func start() {
+        guard !isRunning else { return }
+        knownUUIDs = Dictionary(DisplayManager.activeDisplayIDs().map { ($0, DisplayManager.uuidString(for: $0)) },
+                                uniquingKeysWith: { a, _ in a })
+        let result = CGDisplayRegisterReconfigurationCallback(DisplayWatcher.callback,
+                                                              Unmanaged.passUnretained(self).toOpaque())
+        guard result == .success else {
+            Log.warning("[watch] ディスプレイ監視の登録に失敗: \(result.rawValue)")
+            return
+        }
+        isRunning = true
+        Log.debug("[watch] ディスプレイ監視を開始しました: \(knownUUIDs.count)台")
+    }
+
+    func stop() {
+        guard isRunning else { return }
+        CGDisplayRemoveReconfigurationCallback(DisplayWatcher.callback, Unmanaged.passUnretained(self).toOpaque())
+        isRunning = false
+    }
+
+    // MARK: - コールバック
+
+    private static let callback: CGDisplayReconfigurationCallBack = { displayId, flags, userInfo in
+        guard let userInfo = userInfo, !flags.contains(.beginConfigurationFlag) else { return }
+        let watcher = Unmanaged<DisplayWatcher>.fromOpaque(userInfo).takeUnretainedValue()
+        DispatchQueue.main.async {
+            watcher.handle(displayId: displayId, flags: flags)
+        }
+    }
+
+    private func handle(displayId: CGDirectDisplayID, flags: CGDisplayChangeSummaryFlags) {
+        guard isRunning else { return }
+        if flags.contains(.removeFlag) {
+            let uuid = knownUUIDs.removeValue(forKey: displayId) ?? DisplayManager.uuidString(for: displayId)
+            handler(.disconnected(uuid: uuid))
+            return
+        }
+        let info = DisplayManager.displayInfo(for: displayId)
+        knownUUIDs[displayId] = info.uuid
+        if flags.contains(.addFlag) {
+            handler(.connected(info))
+            return
+        }
+        if flags.contains(.setModeFlag) || flags.contains(.desktopShapeChangedFlag) {
+            handler(.resolutionChanged(info))
+        }
+        if flags.contains(.movedFlag) {
+            handler(.moved(info))
+        }
+        if flags.contains(.setMainFlag) {
+            handler(.mainChanged(info))
+        }
+        if flags.contains(.mirrorFlag) || flags.contains(.unMirrorFlag) {
+            handler(.mirroringChanged(info))
+        }
+    }
+}
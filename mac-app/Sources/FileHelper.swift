@@ -9,6 +9,11 @@ final class FileHelper {
     enum FileHelperError: Error {
         case invalidLayoutName
         case directoryCreationFailed
+        case layoutNotFound
+        case layoutAlreadyExists
+        /// 自動スナップショット用の接頭辞（auto_）で始まる名前は手動では保存できない
+        case reservedLayoutName
+        case profileNotFound
     }
 
     // MARK: - パス解決
@@ -28,17 +33,128 @@ final class FileHelper {
         return cwd.appendingPathComponent("target/window_restore", isDirectory: true)
     }
 
-    static func layoutsDirectoryURL() -> URL {
+    /// レイアウトの保存先（設定 `layouts_directory` があればそちらを優先）
+    static func layoutsDirectoryURL(config: Config = Config.load()) -> URL {
+        if let custom = config.layoutsDirectory, !custom.isEmpty {
+            return URL(fileURLWithPath: (custom as NSString).expandingTildeInPath, isDirectory: true)
+        }
         return baseDirectoryURL().appendingPathComponent("layouts", isDirectory: true)
     }
 
+    /// 設定ファイル（プロファイルが選択されていればそのファイル）
+    /// - config.yaml / config.toml があればそちらを優先。無ければ config.json
+    /// - 選択中のプロファイルが見つからない場合は警告して既定の設定ファイルを使う
     static func configFileURL() -> URL {
-        return baseDirectoryURL().appendingPathComponent("config.json", isDirectory: false)
+        if let profile = activeProfileName() {
+            if let url = try? profileFileURL(name: profile), FileManager.default.fileExists(atPath: url.path) {
+                return url
+            }
+            warnMissingProfile(profile)
+        }
+        return existingFile(in: baseDirectoryURL(), baseName: "config")
+    }
+
+    // configFileURL は頻繁に呼ばれるため、同じプロファイルについての警告は1回のみ
+    private static var warnedMissingProfile: String?
+    private static let warnedMissingProfileLock = NSLock()
+
+    private static func warnMissingProfile(_ name: String) {
+        warnedMissingProfileLock.lock()
+        defer { warnedMissingProfileLock.unlock() }
+        guard warnedMissingProfile != name else { return }
+        warnedMissingProfile = name
+        Log.warning("[config] 選択中のプロファイル「\(name)」が見つからないため、既定の設定ファイルを使います")
+    }
+
+    // MARK: - 設定プロファイル
+
+    static func profilesDirectoryURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("profiles", isDirectory: true)
+    }
+
+    static func profileFileURL(name: String) throws -> URL {
+        guard validateLayoutName(name) else { throw FileHelperError.invalidLayoutName }
+        return existingFile(in: profilesDirectoryURL(), baseName: name)
+    }
+
+    /// 選択中のプロファイル名を記録するファイル
+    static func activeProfileURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("active_profile", isDirectory: false)
+    }
+
+    /// 選択中のプロファイル名（未選択の場合は nil）
+    static func activeProfileName() -> String? {
+        guard let text = try? String(contentsOf: activeProfileURL(), encoding: .utf8) else { return nil }
+        let name = text.trimmingCharacters(in: .whitespacesAndNewlines)
+        return name.isEmpty ? nil : name
+    }
+
+    static func listProfileNames() -> [String] {
+        guard let items = try? FileManager.default.contentsOfDirectory(at: profilesDirectoryURL(),
+                                                                       includingPropertiesForKeys: nil) else {
+            return []
+        }
+        return Set(items
+            .filter { SerializationFormat.supportedExtensions.contains($0.pathExtension.lowercased()) }
+            .map { $0.deletingPathExtension().lastPathComponent })
+            .sorted()
+    }
+
+    // 対応形式のうち既存のファイル（無ければ .json）
+    private static func existingFile(in dir: URL, baseName: String) -> URL {
+        for ext in ["yaml", "yml", "toml"] {
+            let url = dir.appendingPathComponent("\(baseName).\(ext)", isDirectory: false)
+            if FileManager.default.fileExists(atPath: url.path) { return url }
+        }
+        return dir.appendingPathComponent("\(baseName).json", isDirectory: false)
+    }
+
+    /// 直前の復元を取り消すためのスナップショット（レイアウト一覧には含めない）
+    static func undoSnapshotURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("undo_snapshot.json", isDirectory: false)
+    }
+
+    /// 初回起動時の権限設定の進行状況
+    static func onboardingStateURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("onboarding.json", isDirectory: false)
+    }
+
+    /// 権限の確認結果と要求の記録
+    static func permissionAuditLogURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("permission_audit.jsonl", isDirectory: false)
+    }
+
+    /// レイアウトの最終使用日時（同期フォルダの外に置く）
+    static func layoutUsageURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("layout_usage.json", isDirectory: false)
+    }
+
+    static func templatesDirectoryURL() -> URL {
+        return baseDirectoryURL().appendingPathComponent("templates", isDirectory: true)
+    }
+
+    static func templateFileURL(name: String) throws -> URL {
+        guard validateLayoutName(name) else { throw FileHelperError.invalidLayoutName }
+        return templatesDirectoryURL().appendingPathComponent("\(name).json", isDirectory: false)
+    }
+
+    /// レイアウトファイルのURL（既存ファイルがあればその形式、無ければ設定 `layout_format` の形式）
+    static func layoutFileURL(name: String, config: Config = Config.load()) throws -> URL {
+        if let existing = try existingLayoutFileURLs(name: name, config: config).first {
+            return existing
+        }
+        let ext = config.layoutFormat.fileExtension
+        return layoutsDirectoryURL(config: config).appendingPathComponent("\(name).\(ext)", isDirectory: false)
     }
 
-    static func layoutFileURL(name: String) throws -> URL {
+    /// 名前に対応する既存のレイアウトファイル（対応するすべての形式。JSON, YAML, TOML の順）
+    /// - 複数ある場合は layoutFileURL が先頭のものを使い、残りは一覧に現れないまま隠れる
+    static func existingLayoutFileURLs(name: String, config: Config = Config.load()) throws -> [URL] {
         guard validateLayoutName(name) else { throw FileHelperError.invalidLayoutName }
-        return layoutsDirectoryURL().appendingPathComponent("\(name).json", isDirectory: false)
+        let dir = layoutsDirectoryURL(config: config)
+        return ["json", "yaml", "yml", "toml"]
+            .map { dir.appendingPathComponent("\(name).\($0)", isDirectory: false) }
+            .filter { FileManager.default.fileExists(atPath: $0.path) }
     }
 
     // MARK: - 生成/検証
@@ -61,6 +177,19 @@ final class FileHelper {
 
     // MARK: - JSON I/O
 
+    /// 拡張子に応じた形式で保存（未対応の拡張子は JSON）
+    static func save<T: Encodable>(_ value: T, to url: URL) throws {
+        let format = SerializationFormat(url: url) ?? .json
+        try ensureParentDirectory(of: url)
+        try format.encode(value).write(to: url, options: [.atomic])
+    }
+
+    /// 拡張子に応じた形式で読み込み（未対応の拡張子は JSON）
+    static func load<T: Decodable>(_ type: T.Type, from url: URL) throws -> T {
+        let format = SerializationFormat(url: url) ?? .json
+        return try format.decode(T.self, from: Data(contentsOf: url))
+    }
+
     static func saveJSON<T: Encodable>(_ value: T, to url: URL) throws {
         let encoder = JSONEncoder()
         encoder.outputFormatting = [.prettyPrinted, .sortedKeys]
@@ -80,22 +209,141 @@ final class FileHelper {
         try FileManager.default.createDirectory(at: dir, withIntermediateDirectories: true)
     }
 
-    // MARK: - レイアウト一覧/削除
+    // MARK: - バックアップ
 
-    static func listLayoutNames() -> [String] {
+    /// バックアップファイルのURL（`<name>.json.<generation>`、1 が最新）
+    static func backupURL(for url: URL, generation: Int) -> URL {
+        return url.deletingLastPathComponent()
+            .appendingPathComponent("\(url.lastPathComponent).\(generation)", isDirectory: false)
+    }
+
+    /// 既存ファイルを `.1` へ退避し、古い世代を1つずつ繰り下げる（keep を超える世代は削除）
+    /// - 設定で世代数を減らした場合に備えて、keep 以上の世代はまとめて削除する
+    /// - keep が 0 の場合は退避せず、既存のバックアップもそのまま残す（名前の変更時など）
+    static func rotateBackups(of url: URL, keep: Int) throws {
+        let fm = FileManager.default
+        guard keep > 0, fm.fileExists(atPath: url.path) else { return }
+        for generation in backupGenerations(of: url) where generation >= keep {
+            try fm.removeItem(at: backupURL(for: url, generation: generation))
+        }
+        if keep > 1 {
+            for generation in stride(from: keep - 1, through: 1, by: -1) {
+                let from = backupURL(for: url, generation: generation)
+                guard fm.fileExists(atPath: from.path) else { continue }
+                try fm.moveItem(at: from, to: backupURL(for: url, generation: generation + 1))
+            }
+        }
+        try fm.copyItem(at: url, to: backupURL(for: url, generation: 1))
+    }
+
+    /// 存在するバックアップの世代番号（昇順）
+    static func backupGenerations(of url: URL) -> [Int] {
+        let dir = url.deletingLastPathComponent()
+        let prefix = url.lastPathComponent + "."
+        guard let items = try? FileManager.default.contentsOfDirectory(atPath: dir.path) else { return [] }
+        return items.compactMap { item -> Int? in
+            guard item.hasPrefix(prefix) else { return nil }
+            return Int(item.dropFirst(prefix.count))
+        }.sorted()
+    }
+
+    // MARK: - 同期フォルダの競合コピー
+
+    // 同期サービスが競合コピーのファイル名（拡張子なし）に付ける接尾辞
+    // - Dropbox / Nextcloud: "<name> (xxx's conflicted copy 2024-05-01)"
+    // - Syncthing: "<name>.sync-conflict-20240501-123456-ABCDEFG"
+    // - iCloud Drive: "<name> 2"
+    private static let conflictCopyPatterns: [NSRegularExpression] = [
+        #"^(.+?) \([^()]*conflicted copy[^()]*\)$"#,
+        #"^(.+?)\.sync-conflict-\d{8}-\d{6}(?:-[0-9A-Z]+)?$"#,
+        #"^(.+) (?:[2-9]|[1-9]\d+)$"#,
+    ].compactMap { try? NSRegularExpression(pattern: $0, options: [.caseInsensitive]) }
+
+    /// 競合コピーのファイルなら元のレイアウト名を返す
+    /// - 元のレイアウトが existingNames にあり、ファイル内のレイアウト名も元の名前のものだけを競合コピーとみなす
+    ///   （"Work 2" のようにユーザーが保存したレイアウトは、ファイル内の名前が "Work 2" なので対象外）
+    static func conflictCopyLayoutName(of url: URL, existingNames: Set<String>) -> String? {
+        let baseName = url.deletingPathExtension().lastPathComponent
+        let range = NSRange(baseName.startIndex..., in: baseName)
+        for pattern in conflictCopyPatterns {
+            guard let match = pattern.firstMatch(in: baseName, range: range),
+                  let nameRange = Range(match.range(at: 1), in: baseName) else { continue }
+            let name = String(baseName[nameRange])
+            guard existingNames.contains(name),
+                  let data = try? Data(contentsOf: url),
+                  let layout = try? LayoutCrypto.decode(data, format: SerializationFormat(url: url) ?? .json),
+                  layout.name == name else { continue }
+            return name
+        }
+        return nil
+    }
+
+    /// レイアウトフォルダ内の競合コピーと元のレイアウト名
+    static func conflictCopies() -> [(url: URL, layoutName: String)] {
+        let files = layoutFiles()
+        let names = Set(files.map { $0.deletingPathExtension().lastPathComponent })
+        return files.compactMap { url in
+            conflictCopyLayoutName(of: url, existingNames: names).map { (url, $0) }
+        }
+    }
+
+    // レイアウトフォルダ内の対応形式のファイル
+    private static func layoutFiles() -> [URL] {
         let dir = layoutsDirectoryURL()
         guard let items = try? FileManager.default.contentsOfDirectory(at: dir, includingPropertiesForKeys: nil) else {
             return []
         }
+        return items.filter { SerializationFormat.supportedExtensions.contains($0.pathExtension.lowercased()) }
+    }
+
+    // MARK: - レイアウト一覧/削除
+
+    static func listLayoutNames() -> [String] {
+        let files = layoutFiles()
+        let names = Set(files.map { $0.deletingPathExtension().lastPathComponent })
+        return files
+            .filter { conflictCopyLayoutName(of: $0, existingNames: names) == nil }
+            .map { $0.deletingPathExtension().lastPathComponent }
+            .reduce(into: Set<String>()) { $0.insert($1) }
+            .sorted()
+    }
+
+    static func listTemplateNames() -> [String] {
+        guard let items = try? FileManager.default.contentsOfDirectory(at: templatesDirectoryURL(),
+                                                                       includingPropertiesForKeys: nil) else {
+            return []
+        }
         return items
             .filter { $0.pathExtension.lowercased() == "json" }
-            .compactMap { $0.deletingPathExtension().lastPathComponent }
+            .map { $0.deletingPathExtension().lastPathComponent }
             .sorted()
     }
 
+    /// レイアウトファイルとバックアップ世代を新しい名前へ移動
+    static func moveLayout(from oldName: String, to newName: String) throws {
+        let fm = FileManager.default
+        let from = try layoutFileURL(name: oldName)
+        guard validateLayoutName(newName) else { throw FileHelperError.invalidLayoutName }
+        // 形式（拡張子）は変更前のファイルに合わせる
+        let to = layoutsDirectoryURL().appendingPathComponent("\(newName).\(from.pathExtension)", isDirectory: false)
+        guard fm.fileExists(atPath: from.path) else { throw FileHelperError.layoutNotFound }
+        // 別の形式で同じ名前のファイルがある場合も、どちらかが隠れるため移動しない
+        guard try existingLayoutFileURLs(name: newName).isEmpty else { throw FileHelperError.layoutAlreadyExists }
+        try fm.moveItem(at: from, to: to)
+        for generation in backupGenerations(of: from) {
+            let target = backupURL(for: to, generation: generation)
+            try? fm.removeItem(at: target)
+            try fm.moveItem(at: backupURL(for: from, generation: generation), to: target)
+        }
+    }
+
+    /// レイアウトファイルとそのバックアップ世代を削除
     static func deleteLayout(name: String) throws {
         let url = try layoutFileURL(name: name)
         try FileManager.default.removeItem(at: url)
+        for generation in backupGenerations(of: url) {
+            try? FileManager.default.removeItem(at: backupURL(for: url, generation: generation))
+        }
     }
 }
 
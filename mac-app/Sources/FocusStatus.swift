@@ -0,0 +1,23 @@
+import Foundation
+import Intents
+
+/// 集中モード（おやすみモードを含む）の状態
+/// - INFocusStatusCenter を使う。.app の Info.plist に NSFocusStatusUsageDescription が必要で、
+///   ユーザーが「集中モードの状態の共有」を許可していない場合は常に無効として扱う
+enum FocusStatus {
+
+    /// 集中モードが有効か（判定できない場合は false）
+    static var isActive: Bool {
+        let center = INFocusStatusCenter.default
+        guard center.authorizationStatus == .authorized else { return false }
+        return center.focusStatus.isFocused ?? false
+    }
+
+    /// 集中モードの状態の共有を要求（未確認の場合のみダイアログが出る）
+    static func requestAuthorization() {
+        guard INFocusStatusCenter.default.authorizationStatus == .notDetermined else { return }
+        INFocusStatusCenter.default.requestAuthorization { status in
+            Log.debug("[notify] 集中モードの状態の共有: \(status.rawValue)")
+        }
+    }
+}
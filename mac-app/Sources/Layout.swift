@@ -0,0 +1,198 @@
+import Foundation
+
+/// 保存済みレイアウト（`<base>/layouts/<name>.json`）
+/// - キーは REQUIREMENTS.md 3.1 に準拠（日時は ISO 8601 文字列）
+/// - 旧形式（WindowInfo の配列のみのファイル）も読み込める
+struct Layout: Codable {
+    var name: String
+    var windows: [WindowInfo]
+    var createdAt: Date
+    var updatedAt: Date
+    /// 復元完了後にアクティブにするアプリ（bundle id）
+    var focusApp: String? = nil
+    /// 説明
+    var description: String? = nil
+    /// 整理用のタグ
+    var tags: [String] = []
+    /// 割り当てたショートカットの表示用文字列（例: "⌃⌥1"）
+    var shortcut: String? = nil
+    /// 保存時のディスプレイ構成（旧データは nil）
+    var displays: [DisplayInfo]? = nil
+    /// 固定（上限超過時の自動削除の対象外）
+    var pinned: Bool = false
+    /// 最後に復元した日時（旧データのみ。現在は LayoutUsageIndex に記録する）
+    var lastUsedAt: Date? = nil
+
+    enum CodingKeys: String, CodingKey {
+        case name = "layout_name"
+        case windows
+        case createdAt = "created_at"
+        case updatedAt = "updated_at"
+        case focusApp = "focus_app"
+        case description
+        case tags
+        case shortcut
+        case displays
+        case pinned
+        case lastUsedAt = "last_used_at"
+    }
+
+    init(name: String, windows: [WindowInfo], createdAt: Date = Date(), updatedAt: Date? = nil) {
+        self.name = name
+        self.windows = windows
+        self.createdAt = createdAt
+        self.updatedAt = updatedAt ?? createdAt
+    }
+
+    init(from decoder: Decoder) throws {
+        // 旧形式: ウィンドウ配列のみ（名前はファイル名、日時は読み込み側で補完）
+        if let legacy = try? decoder.singleValueContainer().decode([WindowInfo].self) {
+            self.init(name: "", windows: legacy, createdAt: .distantPast)
+            return
+        }
+        let c = try decoder.container(keyedBy: CodingKeys.self)
+        name = try c.decodeIfPresent(String.self, forKey: .name) ?? ""
+        windows = try c.decode([WindowInfo].self, forKey: .windows)
+        createdAt = try Layout.decodeDate(c, .createdAt) ?? .distantPast
+        updatedAt = try Layout.decodeDate(c, .updatedAt) ?? createdAt
+        focusApp = try c.decodeIfPresent(String.self, forKey: .focusApp)
+        description = try c.decodeIfPresent(String.self, forKey: .description)
+        tags = try c.decodeIfPresent([String].self, forKey: .tags) ?? []
+        shortcut = try c.decodeIfPresent(String.self, forKey: .shortcut)
+        displays = try c.decodeIfPresent([DisplayInfo].self, forKey: .displays)
+        pinned = try c.decodeIfPresent(Bool.self, forKey: .pinned) ?? false
+        lastUsedAt = try Layout.decodeDate(c, .lastUsedAt)
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var c = encoder.container(keyedBy: CodingKeys.self)
+        try c.encode(name, forKey: .name)
+        try c.encode(windows, forKey: .windows)
+        try c.encode(Layout.dateFormatter.string(from: createdAt), forKey: .createdAt)
+        try c.encode(Layout.dateFormatter.string(from: updatedAt), forKey: .updatedAt)
+        try c.encodeIfPresent(focusApp, forKey: .focusApp)
+        try c.encodeIfPresent(description, forKey: .description)
+        if !tags.isEmpty { try c.encode(tags, forKey: .tags) }
+        try c.encodeIfPresent(shortcut, forKey: .shortcut)
+        try c.encodeIfPresent(displays, forKey: .displays)
+        if pinned { try c.encode(pinned, forKey: .pinned) }
+        try c.encodeIfPresent(lastUsedAt.map { Layout.dateFormatter.string(from: $0) }, forKey: .lastUsedAt)
+    }
+
+    /// 保存時と現在のディスプレイ構成の一致度（構成が記録されていない場合は nil）
+    func displayMatch() -> DisplayMatch? {
+        return displays.map { DisplayManager.compare(saved: $0) }
+    }
+
+    /// 保存時のディスプレイ配置の指紋（DisplayManager.arrangementFingerprint。構成が記録されていない場合は nil）
+    var displayArrangement: String? {
+        guard let displays = displays, !displays.isEmpty else { return nil }
+        return DisplayManager.arrangementFingerprint(displays)
+    }
+
+    // MARK: - メタデータ
+
+    /// ウィンドウ一覧以外の編集可能な情報
+    var metadata: LayoutMetadata {
+        get { LayoutMetadata(description: description, tags: tags, shortcut: shortcut) }
+        set {
+            description = newValue.description.flatMap { $0.isEmpty ? nil : $0 }
+            tags = LayoutMetadata.normalizedTags(newValue.tags)
+            shortcut = newValue.shortcut.flatMap { $0.isEmpty ? nil : $0 }
+        }
+    }
+
+    // MARK: - 検索
+
+    /// 検索語（空白区切りのすべての語）が名前・説明・タグ・含まれるアプリ名/bundle id のいずれかに含まれるか
+    /// - 大文字小文字は区別しない。空の検索語はすべてに一致する
+    func matches(query: String) -> Bool {
+        let terms = query.split(whereSeparator: { $0.isWhitespace }).map(String.init)
+        guard !terms.isEmpty else { return true }
+        var fields = [name, description ?? ""] + tags
+        for w in windows {
+            fields.append(w.ownerName)
+            if let bundleId = w.bundleId { fields.append(bundleId) }
+        }
+        return terms.allSatisfy { term in
+            fields.contains { $0.range(of: term, options: [.caseInsensitive, .diacriticInsensitive]) != nil }
+        }
+    }
+
+    // MARK: - 日時
+
+    private static let dateFormatter: ISO8601DateFormatter = {
+        let formatter = ISO8601DateFormatter()
+        formatter.formatOptions = [.withInternetDateTime]
+        return formatter
+    }()
+
+    private static func decodeDate(_ c: KeyedDecodingContainer<CodingKeys>, _ key: CodingKeys) throws -> Date? {
+        guard let text = try c.decodeIfPresent(String.self, forKey: key) else { return nil }
+        return dateFormatter.date(from: text)
+    }
+}
+
+/// レイアウトの概要（一覧やプレビューの表示用。ウィンドウ一覧そのものは含まない）
+struct LayoutSummary {
+    /// アプリごとのウィンドウ数
+    struct AppEntry: Equatable {
+        let bundleId: String?
+        let name: String
+        let windowCount: Int
+    }
+
+    let name: String
+    let windowCount: Int
+    /// 保存順（最初に現れた順）のアプリ一覧
+    let apps: [AppEntry]
+    let createdAt: Date
+    let updatedAt: Date
+    let metadata: LayoutMetadata
+    let focusApp: String?
+    /// 保存時と現在のディスプレイ構成の一致度（構成が記録されていない場合は nil）
+    let displayMatch: DisplayMatch?
+    /// 保存時のディスプレイ配置の指紋
+    let displayArrangement: String?
+    /// 保存時のディスプレイの表示用の名前（別名があれば別名）
+    let displayLabels: [String]
+
+    init(_ layout: Layout) {
+        var order: [String] = []
+        var entries: [String: (bundleId: String?, name: String, count: Int)] = [:]
+        for w in layout.windows {
+            let key = w.bundleId ?? w.ownerName
+            if entries[key] == nil {
+                order.append(key)
+                entries[key] = (w.bundleId, w.ownerName, 0)
+            }
+            entries[key]?.count += 1
+        }
+        self.name = layout.name
+        self.windowCount = layout.windows.count
+        self.apps = order.compactMap { entries[$0] }.map { AppEntry(bundleId: $0.bundleId, name: $0.name, windowCount: $0.count) }
+        self.createdAt = layout.createdAt
+        self.updatedAt = layout.updatedAt
+        self.metadata = layout.metadata
+        self.focusApp = layout.focusApp
+        self.displayMatch = layout.displayMatch()
+        self.displayArrangement = layout.displayArrangement
+        let config = Config.load()
+        self.displayLabels = (layout.displays ?? []).map { $0.label(config: config) }
+    }
+}
+
+/// レイアウトの説明・タグ・ショートカット
+struct LayoutMetadata: Equatable {
+    var description: String?
+    var tags: [String]
+    var shortcut: String?
+
+    /// 前後の空白を除き、空文字と重複（大文字小文字を区別しない）を取り除く
+    static func normalizedTags(_ tags: [String]) -> [String] {
+        var seen = Set<String>()
+        return tags
+            .map { $0.trimmingCharacters(in: .whitespacesAndNewlines) }
+            .filter { !$0.isEmpty && seen.insert($0.lowercased()).inserted }
+    }
+}
@@ -0,0 +1,33 @@
+import Foundation
+
+/// 複数レイアウトを1ファイルにまとめた可搬アーカイブ（Mac 間の移行・共有用）
+struct LayoutArchive: Codable {
+    /// 現在のアーカイブ形式のバージョン
+    static let currentVersion = 1
+
+    var version: Int
+    var exportedAt: String
+    var layouts: [Layout]
+
+    enum CodingKeys: String, CodingKey {
+        case version
+        case exportedAt = "exported_at"
+        case layouts
+    }
+
+    init(layouts: [Layout]) {
+        self.version = LayoutArchive.currentVersion
+        self.exportedAt = ISO8601DateFormatter().string(from: Date())
+        self.layouts = layouts
+    }
+}
+
+/// インポート時に同名のレイアウトが既に存在する場合の扱い
+enum ImportConflictPolicy {
+    /// 既存を残し、インポートしない
+    case skip
+    /// 既存を上書き（上書き前の内容はバックアップに残る）
+    case overwrite
+    /// "名前 (2)" のように別名で取り込む
+    case rename
+}
@@ -0,0 +1,109 @@
+import Foundation
+import CryptoKit
+import Security
+
+/// レイアウトの暗号化（AES-GCM）
+/// - ウィンドウタイトルには文書名や URL が含まれるため、保存時に暗号化できるようにする
+/// - 鍵はこの Mac のキーチェーンに保管するため、暗号化したレイアウトは他の Mac では読めない
+///   （移行にはエクスポート/インポートを使う。アーカイブは平文）
+/// - 平文のデータはそのまま読み込めるので、設定を切り替えても既存のレイアウトは失われない
+/// - テンプレートと取り消し用スナップショットも同じ設定で暗号化する
+enum LayoutCrypto {
+    enum CryptoError: Error {
+        case keychain(OSStatus)
+        case malformed
+    }
+
+    // 暗号化データの先頭に付ける識別子
+    private static let magic = Data("WRENC1".utf8)
+    private static let keychainService = "window_restore.layout-encryption"
+    private static let keychainAccount = "default"
+
+    private static let lock = NSLock()
+    private static var cachedKey: SymmetricKey?
+
+    /// 暗号化済みデータか
+    static func isEncrypted(_ data: Data) -> Bool {
+        return data.starts(with: magic)
+    }
+
+    static func seal(_ data: Data) throws -> Data {
+        let box = try AES.GCM.seal(data, using: key())
+        guard let combined = box.combined else { throw CryptoError.malformed }
+        return magic + combined
+    }
+
+    /// 暗号化済みなら復号し、平文ならそのまま返す
+    static func open(_ data: Data) throws -> Data {
+        guard isEncrypted(data) else { return data }
+        let box = try AES.GCM.SealedBox(combined: data.dropFirst(magic.count))
+        return try AES.GCM.open(box, using: key())
+    }
+
+    // MARK: - レイアウト
+
+    /// 暗号化する場合は形式によらず JSON を暗号化する
+    static func encode<T: Encodable>(_ value: T, format: SerializationFormat = .json, encrypt: Bool) throws -> Data {
+        guard encrypt else { return try format.encode(value) }
+        let encoder = JSONEncoder()
+        encoder.outputFormatting = [.sortedKeys]
+        return try seal(encoder.encode(value))
+    }
+
+    static func decode<T: Decodable>(_ type: T.Type, from data: Data, format: SerializationFormat = .json) throws -> T {
+        if isEncrypted(data) {
+            return try JSONDecoder().decode(T.self, from: open(data))
+        }
+        return try format.decode(T.self, from: data)
+    }
+
+    static func decode(_ data: Data, format: SerializationFormat = .json) throws -> Layout {
+        return try decode(Layout.self, from: data, format: format)
+    }
+
+    /// JSON ファイルを encrypt に合わせて暗号化/復号して書き直す（既に合っている場合や存在しない場合は何もしない）
+    /// 戻り値: 書き直した場合 true
+    @discardableResult
+    static func rewriteJSONFile(at url: URL, encrypt: Bool) throws -> Bool {
+        guard let data = try? Data(contentsOf: url), isEncrypted(data) != encrypt else { return false }
+        try (encrypt ? seal(data) : open(data)).write(to: url, options: [.atomic])
+        return true
+    }
+
+    // MARK: - 鍵
+
+    // キーチェーンから鍵を取得（無ければ生成して登録）
+    private static func key() throws -> SymmetricKey {
+        lock.lock()
+        defer { lock.unlock() }
+        if let key = cachedKey { return key }
+
+        let query: [String: Any] = [
+            kSecClass as String: kSecClassGenericPassword,
+            kSecAttrService as String: keychainService,
+            kSecAttrAccount as String: keychainAccount,
+        ]
+        var item: CFTypeRef?
+        var status = SecItemCopyMatching(query.merging([
+            kSecReturnData as String: true,
+            kSecMatchLimit as String: kSecMatchLimitOne,
+        ]) { $1 } as CFDictionary, &item)
+        if status == errSecSuccess, let data = item as? Data {
+            let key = SymmetricKey(data: data)
+            cachedKey = key
+            return key
+        }
+        guard status == errSecItemNotFound else { throw CryptoError.keychain(status) }
+
+        let key = SymmetricKey(size: .bits256)
+        let keyData = key.withUnsafeBytes { Data($0) }
+        status = SecItemAdd(query.merging([
+            kSecValueData as String: keyData,
+            kSecAttrAccessible as String: kSecAttrAccessibleAfterFirstUnlockThisDeviceOnly,
+        ]) { $1 } as CFDictionary, nil)
+        guard status == errSecSuccess else { throw CryptoError.keychain(status) }
+        Log.info("[layout] 暗号化キーを生成してキーチェーンに登録しました")
+        cachedKey = key
+        return key
+    }
+}
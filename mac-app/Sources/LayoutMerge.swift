@@ -0,0 +1,56 @@
+import Foundation
+
+/// 2つのレイアウトを合成する際の競合（同一アプリの同一ウィンドウ）の解決方法
+enum LayoutMergeStrategy {
+    /// 競合したウィンドウは overlay 側を採用
+    case preferOverlay
+    /// 競合したウィンドウは base 側を採用
+    case preferBase
+    /// overlay に含まれるアプリは、base 側のウィンドウをすべて overlay 側で置き換える
+    case replaceApps
+}
+
+extension Layout {
+    /// overlay を合成したレイアウトを返す（名前・作成日時・メタデータは base 側を引き継ぐ）
+    /// - 同一ウィンドウの判定はアプリ（bundle id、無ければアプリ名）＋ AXIdentifier、無ければタイトル
+    /// - PID / CGWindowNumber は保存時のプロセスでしか意味を持たないため使用しない
+    func merged(with overlay: Layout, strategy: LayoutMergeStrategy) -> Layout {
+        var result = self
+        switch strategy {
+        case .replaceApps:
+            let overlayApps = Set(overlay.windows.map(Layout.appKey))
+            result.windows = windows.filter { !overlayApps.contains(Layout.appKey($0)) } + overlay.windows
+        case .preferOverlay, .preferBase:
+            var merged = windows
+            // 同一キーのウィンドウが複数ある場合は出現順に1対1で対応付ける
+            var slots: [String: [Int]] = [:]
+            for (i, w) in windows.enumerated() {
+                slots[Layout.mergeKey(w), default: []].append(i)
+            }
+            for w in overlay.windows {
+                let key = Layout.mergeKey(w)
+                if var bucket = slots[key], !bucket.isEmpty {
+                    let index = bucket.removeFirst()
+                    slots[key] = bucket
+                    if strategy == .preferOverlay { merged[index] = w }
+                } else {
+                    merged.append(w)
+                }
+            }
+            result.windows = merged
+        }
+        if result.focusApp == nil { result.focusApp = overlay.focusApp }
+        return result
+    }
+
+    private static func appKey(_ w: WindowInfo) -> String {
+        return w.bundleId ?? w.ownerName
+    }
+
+    private static func mergeKey(_ w: WindowInfo) -> String {
+        if let identifier = w.axIdentifier, !identifier.isEmpty {
+            return "\(appKey(w))|id:\(identifier)"
+        }
+        return "\(appKey(w))|title:\(w.windowName ?? "")"
+    }
+}
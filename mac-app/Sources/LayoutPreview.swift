@@ -0,0 +1,84 @@
+import Foundation
+import CoreGraphics
+
+/// レイアウトの配置を模式的に表したプレビュー（復元前の確認用）
+/// - 座標は全体（保存時のディスプレイ、無ければ全ウィンドウ）を囲む矩形に対する 0〜1 の比率
+/// - ウィンドウは奥から手前の順（描画順）に並ぶ
+struct LayoutPreview {
+    struct Item: Equatable {
+        let appName: String
+        let bundleId: String?
+        let title: String?
+        let rect: CGRect
+    }
+
+    /// 保存時のディスプレイ（構成が記録されていない場合は空）
+    let displays: [CGRect]
+    let windows: [Item]
+    /// 全体の縦横比（幅 / 高さ）
+    let aspectRatio: CGFloat
+
+    init(_ layout: Layout) {
+        // 最小化/非表示など画面に出ていなかったウィンドウは描かない
+        let visible = layout.windows
+            .filter { $0.isMinimized != true && $0.isHidden != true && $0.isOffscreen != true }
+            .sorted { ($0.zIndex ?? Int.max) > ($1.zIndex ?? Int.max) }
+        let displayFrames = layout.displays?.map(\.bounds) ?? []
+        let canvas = (displayFrames.isEmpty ? visible.map(\.bounds) : displayFrames)
+            .reduce(CGRect.null) { $0.union($1) }
+
+        guard !canvas.isNull, canvas.width > 0, canvas.height > 0 else {
+            self.displays = []
+            self.windows = []
+            self.aspectRatio = 16.0 / 9.0
+            return
+        }
+        func normalize(_ r: CGRect) -> CGRect {
+            return DisplayManager.normalized(r, in: canvas) ?? .zero
+        }
+        self.displays = displayFrames.map(normalize)
+        self.windows = visible.map {
+            Item(appName: $0.ownerName, bundleId: $0.bundleId, title: $0.windowName, rect: normalize($0.bounds))
+        }
+        self.aspectRatio = canvas.width / canvas.height
+    }
+
+    // MARK: - SVG
+
+    /// SVG 文字列（幅を指定し、高さは縦横比から決める）
+    func svg(width: CGFloat = 320) -> String {
+        let height = (width / aspectRatio).rounded()
+        var parts = [#"<svg xmlns="http://www.w3.org/2000/svg" width="\#(Int(width))" height="\#(Int(height))" viewBox="0 0 \#(Int(width)) \#(Int(height))">"#]
+        for d in displays {
+            parts.append(rect(d, width: width, height: height, fill: "#f2f2f2", stroke: "#999999"))
+        }
+        for w in windows {
+            let label = LayoutPreview.escaped(w.appName)
+            parts.append(rect(w.rect, width: width, height: height, fill: LayoutPreview.color(for: w.bundleId ?? w.appName), stroke: "#333333",
+                              title: label))
+        }
+        parts.append("</svg>")
+        return parts.joined(separator: "\n")
+    }
+
+    private func rect(_ r: CGRect, width: CGFloat, height: CGFloat, fill: String, stroke: String, title: String? = nil) -> String {
+        let x = String(format: "%.1f", r.minX * width)
+        let y = String(format: "%.1f", r.minY * height)
+        let w = String(format: "%.1f", r.width * width)
+        let h = String(format: "%.1f", r.height * height)
+        let body = title.map { "<title>\($0)</title>" } ?? ""
+        return #"<rect x="\#(x)" y="\#(y)" width="\#(w)" height="\#(h)" fill="\#(fill)" fill-opacity="0.8" stroke="\#(stroke)" stroke-width="1">\#(body)</rect>"#
+    }
+
+    // アプリごとに安定した色（bundle id のハッシュから色相を決める）
+    private static func color(for key: String) -> String {
+        let hash = key.unicodeScalars.reduce(UInt32(5381)) { ($0 &* 33) &+ $1.value }
+        return "hsl(\(hash % 360), 55%, 70%)"
+    }
+
+    private static func escaped(_ text: String) -> String {
+        return text.replacingOccurrences(of: "&", with: "&amp;")
+            .replacingOccurrences(of: "<", with: "&lt;")
+            .replacingOccurrences(of: ">", with: "&gt;")
+    }
+}
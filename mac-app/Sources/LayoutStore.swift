@@ -0,0 +1,411 @@
+import Foundation
+import SQLite3
+
+/// レイアウトの保存先の種類（config.json の `storage_backend`）
+enum LayoutStorageBackend: String, Codable {
+    /// `<base>/layouts/<name>.json`（既定。.yaml / .toml も可）
+    case file
+    /// `<base>/layouts.sqlite3`（レイアウト数や自動保存が多い場合向け）
+    case sqlite
+}
+
+/// レイアウトの永続化先
+/// - 保存先を切り替えても既存データは移行されない（エクスポート/インポートで移す）
+protocol LayoutStore {
+    func listNames() -> [String]
+    func load(name: String) throws -> Layout
+    /// 上書き前の内容を keepBackups 世代まで退避してから保存（0 の場合は退避しない）
+    func save(_ layout: Layout, name: String, keepBackups: Int) throws
+    func delete(name: String) throws
+    /// 名前を変更（バックアップ世代も引き継ぐ）
+    func rename(from oldName: String, to newName: String) throws
+    /// バックアップの世代番号（昇順、1 が最新）
+    func backupGenerations(name: String) -> [Int]
+    func loadBackup(name: String, generation: Int) throws -> Layout
+    /// 指定アプリ（bundle id またはアプリ名）のウィンドウを含むレイアウト名
+    func names(containingApp app: String) -> [String]
+    /// 複数の変更をまとめて実行（対応するバックエンドでは失敗時にすべて取り消す）
+    func transaction(_ body: () throws -> Void) throws
+    /// 保存済みのレイアウトとバックアップを encrypt に合わせて暗号化/復号し直す（合っているものはそのまま）
+    /// 戻り値: 書き直した件数
+    func applyEncryption(_ encrypt: Bool) throws -> Int
+}
+
+enum LayoutStores {
+    private static let sqlite = SQLiteLayoutStore(url: FileHelper.baseDirectoryURL().appendingPathComponent("layouts.sqlite3"))
+
+    static func store(for backend: LayoutStorageBackend) -> LayoutStore {
+        switch backend {
+        case .file: return FileLayoutStore()
+        case .sqlite: return sqlite
+        }
+    }
+}
+
+// MARK: - ファイル
+
+/// レイアウトごとのファイル（従来の形式。拡張子に応じて JSON / YAML / TOML）
+struct FileLayoutStore: LayoutStore {
+
+    func listNames() -> [String] {
+        return FileHelper.listLayoutNames()
+    }
+
+    /// 旧形式は名前とファイル更新日時を補完する
+    /// - ファイルが無い場合は FileHelperError.layoutNotFound（SQLite と同じく、読み込みの失敗と区別できるように）
+    func load(name: String) throws -> Layout {
+        let url = try FileHelper.layoutFileURL(name: name)
+        guard FileManager.default.fileExists(atPath: url.path) else { throw FileHelper.FileHelperError.layoutNotFound }
+        var layout = try LayoutCrypto.decode(Data(contentsOf: url), format: SerializationFormat(url: url) ?? .json)
+        if layout.name.isEmpty { layout.name = name }
+        if layout.createdAt == .distantPast {
+            let modified = (try? FileManager.default.attributesOfItem(atPath: url.path)[.modificationDate]) as? Date
+            layout.createdAt = modified ?? Date()
+            layout.updatedAt = layout.createdAt
+        }
+        return layout
+    }
+
+    func save(_ layout: Layout, name: String, keepBackups: Int) throws {
+        try FileHelper.ensureDirectories()
+        let config = Config.load()
+        // 同じ名前のファイルが複数の形式である場合は、どちらを上書きしても片方が隠れたままになるため保存しない
+        let existing = try FileHelper.existingLayoutFileURLs(name: name, config: config)
+        guard existing.count <= 1 else {
+            Log.warning("[layout] 同じ名前のレイアウトが複数の形式で存在します: \(existing.map(\.lastPathComponent).joined(separator: ", "))")
+            throw FileHelper.FileHelperError.layoutAlreadyExists
+        }
+        let url = try FileHelper.layoutFileURL(name: name, config: config)
+        let format = SerializationFormat(url: url) ?? .json
+        do {
+            try FileHelper.rotateBackups(of: url, keep: keepBackups)
+            // 退避したのは上書き前のファイルのため、暗号化の設定を切り替えた直後は平文のことがある
+            if keepBackups > 0 {
+                try rewrite(FileHelper.backupURL(for: url, generation: 1), format: format, encrypt: config.encryptLayouts)
+            }
+        } catch {
+            Log.warning("[layout] バックアップの作成に失敗: \(error.localizedDescription)")
+        }
+        let data = try LayoutCrypto.encode(layout, format: format, encrypt: config.encryptLayouts)
+        try data.write(to: url, options: [.atomic])
+    }
+
+    func delete(name: String) throws {
+        try FileHelper.deleteLayout(name: name)
+    }
+
+    func rename(from oldName: String, to newName: String) throws {
+        try FileHelper.moveLayout(from: oldName, to: newName)
+    }
+
+    func backupGenerations(name: String) -> [Int] {
+        guard let url = try? FileHelper.layoutFileURL(name: name) else { return [] }
+        return FileHelper.backupGenerations(of: url)
+    }
+
+    func loadBackup(name: String, generation: Int) throws -> Layout {
+        let layoutURL = try FileHelper.layoutFileURL(name: name)
+        let url = FileHelper.backupURL(for: layoutURL, generation: generation)
+        var layout = try LayoutCrypto.decode(Data(contentsOf: url), format: SerializationFormat(url: layoutURL) ?? .json)
+        if layout.name.isEmpty { layout.name = name }
+        return layout
+    }
+
+    func names(containingApp app: String) -> [String] {
+        return listNames().filter { name in
+            guard let layout = try? load(name: name) else { return false }
+            return layout.windows.contains { $0.belongs(toAnyOf: [app]) }
+        }
+    }
+
+    func transaction(_ body: () throws -> Void) throws {
+        try body()
+    }
+
+    func applyEncryption(_ encrypt: Bool) throws -> Int {
+        var rewritten = 0
+        for name in listNames() {
+            let url = try FileHelper.layoutFileURL(name: name)
+            let format = SerializationFormat(url: url) ?? .json
+            let backups = FileHelper.backupGenerations(of: url).map { FileHelper.backupURL(for: url, generation: $0) }
+            for file in [url] + backups {
+                if try rewrite(file, format: format, encrypt: encrypt) { rewritten += 1 }
+            }
+        }
+        return rewritten
+    }
+
+    // 暗号化の有無が encrypt と異なるファイルを書き直す（暗号化する場合は形式によらず JSON になる）
+    // 戻り値: 書き直した場合 true
+    private func rewrite(_ url: URL, format: SerializationFormat, encrypt: Bool) throws -> Bool {
+        guard let data = try? Data(contentsOf: url), LayoutCrypto.isEncrypted(data) != encrypt else { return false }
+        let layout = try LayoutCrypto.decode(data, format: format)
+        try LayoutCrypto.encode(layout, format: format, encrypt: encrypt).write(to: url, options: [.atomic])
+        return true
+    }
+}
+
+// MARK: - SQLite
+
+/// 単一の SQLite データベース
+/// - レイアウトは JSON テキストとして保存し、アプリ検索などは JSON1 関数で行う
+/// - 暗号化したレイアウトは `enc:` + Base64 として保存する（検索は復号して行う）
+/// - 上書きとバックアップの退避は1トランザクションで行う
+final class SQLiteLayoutStore: LayoutStore {
+    enum SQLiteError: Error {
+        case openFailed(String)
+        case statementFailed(String)
+    }
+
+    private let url: URL
+    private var db: OpaquePointer?
+    // transaction の入れ子で同じスレッドから再取得するため再帰ロック
+    private let lock = NSRecursiveLock()
+    private var savepointDepth = 0
+
+    init(url: URL) {
+        self.url = url
+    }
+
+    deinit {
+        sqlite3_close(db)
+    }
+
+    func listNames() -> [String] {
+        return (try? query("SELECT name FROM layouts ORDER BY name") { Self.text($0, 0) }) ?? []
+    }
+
+    func load(name: String) throws -> Layout {
+        let rows = try query("SELECT data FROM layouts WHERE name = ?", [name]) { Self.text($0, 0) }
+        guard let json = rows.first else { throw FileHelper.FileHelperError.layoutNotFound }
+        return try decode(json, name: name)
+    }
+
+    func save(_ layout: Layout, name: String, keepBackups: Int) throws {
+        guard FileHelper.validateLayoutName(name) else { throw FileHelper.FileHelperError.invalidLayoutName }
+        let encrypt = Config.load().encryptLayouts
+        let json = try encode(layout, encrypt: encrypt)
+        try transaction {
+            if keepBackups > 0 {
+                try execute("DELETE FROM layout_backups WHERE name = ? AND generation >= ?", [name, keepBackups])
+                // 主キーの一時的な重複を避けるため、いったん負数にしてから繰り下げる
+                try execute("UPDATE layout_backups SET generation = -(generation + 1) WHERE name = ?", [name])
+                try execute("UPDATE layout_backups SET generation = -generation WHERE name = ?", [name])
+                // 暗号化の設定を切り替えた直後は上書き前の内容が平文のことがあるため、設定に合わせてから退避する
+                if let current = try query("SELECT data FROM layouts WHERE name = ?", [name], row: { Self.text($0, 0) }).first {
+                    try execute("INSERT INTO layout_backups (name, generation, data) VALUES (?, 1, ?)",
+                                [name, try converted(current, name: name, encrypt: encrypt)])
+                }
+            }
+            try execute("INSERT OR REPLACE INTO layouts (name, data) VALUES (?, ?)", [name, json])
+        }
+    }
+
+    func delete(name: String) throws {
+        try transaction {
+            guard try exists(name) else { throw FileHelper.FileHelperError.layoutNotFound }
+            try execute("DELETE FROM layouts WHERE name = ?", [name])
+            try execute("DELETE FROM layout_backups WHERE name = ?", [name])
+        }
+    }
+
+    func rename(from oldName: String, to newName: String) throws {
+        guard FileHelper.validateLayoutName(newName) else { throw FileHelper.FileHelperError.invalidLayoutName }
+        try transaction {
+            guard try exists(oldName) else { throw FileHelper.FileHelperError.layoutNotFound }
+            guard try !exists(newName) else { throw FileHelper.FileHelperError.layoutAlreadyExists }
+            try execute("DELETE FROM layout_backups WHERE name = ?", [newName])
+            try execute("UPDATE layouts SET name = ? WHERE name = ?", [newName, oldName])
+            try execute("UPDATE layout_backups SET name = ? WHERE name = ?", [newName, oldName])
+        }
+    }
+
+    func backupGenerations(name: String) -> [Int] {
+        let sql = "SELECT generation FROM layout_backups WHERE name = ? ORDER BY generation"
+        return (try? query(sql, [name]) { Int(sqlite3_column_int64($0, 0)) }) ?? []
+    }
+
+    func loadBackup(name: String, generation: Int) throws -> Layout {
+        let sql = "SELECT data FROM layout_backups WHERE name = ? AND generation = ?"
+        let rows = try query(sql, [name, generation]) { Self.text($0, 0) }
+        guard let json = rows.first else { throw FileHelper.FileHelperError.layoutNotFound }
+        return try decode(json, name: name)
+    }
+
+    func names(containingApp app: String) -> [String] {
+        let pattern = app.trimmingCharacters(in: .whitespacesAndNewlines)
+        guard !pattern.isEmpty else { return [] }
+        let sql = """
+        SELECT DISTINCT l.name
+        FROM (SELECT name, data FROM layouts WHERE json_valid(data)) l, json_each(l.data, '$.windows') w
+        WHERE json_extract(w.value, '$.bundleId') = ?1
+           OR lower(json_extract(w.value, '$.ownerName')) = lower(?1)
+        """
+        var names = (try? query(sql, [pattern]) { Self.text($0, 0) }) ?? []
+        let encrypted = (try? query("SELECT name, data FROM layouts WHERE data LIKE 'enc:%'") {
+            (Self.text($0, 0), Self.text($0, 1))
+        }) ?? []
+        for (name, json) in encrypted {
+            guard let layout = try? decode(json, name: name) else { continue }
+            if layout.windows.contains(where: { $0.belongs(toAnyOf: [pattern]) }) { names.append(name) }
+        }
+        return names.sorted()
+    }
+
+    /// SAVEPOINT を使うため入れ子にできる
+    func transaction(_ body: () throws -> Void) throws {
+        lock.lock()
+        defer { lock.unlock() }
+        savepointDepth += 1
+        defer { savepointDepth -= 1 }
+        let savepoint = "sp\(savepointDepth)"
+        try execute("SAVEPOINT \(savepoint)")
+        do {
+            try body()
+            try execute("RELEASE \(savepoint)")
+        } catch {
+            try? execute("ROLLBACK TO \(savepoint)")
+            try? execute("RELEASE \(savepoint)")
+            throw error
+        }
+    }
+
+    /// 暗号化を有効にした場合は、書き換え前の平文が空き領域に残らないよう VACUUM する
+    func applyEncryption(_ encrypt: Bool) throws -> Int {
+        var rewritten = 0
+        try transaction {
+            for (name, text) in try query("SELECT name, data FROM layouts", row: { (Self.text($0, 0), Self.text($0, 1)) }) {
+                let data = try converted(text, name: name, encrypt: encrypt)
+                guard data != text else { continue }
+                try execute("UPDATE layouts SET data = ? WHERE name = ?", [data, name])
+                rewritten += 1
+            }
+            let backups = try query("SELECT name, generation, data FROM layout_backups") {
+                (Self.text($0, 0), Int(sqlite3_column_int64($0, 1)), Self.text($0, 2))
+            }
+            for (name, generation, text) in backups {
+                let data = try converted(text, name: name, encrypt: encrypt)
+                guard data != text else { continue }
+                try execute("UPDATE layout_backups SET data = ? WHERE name = ? AND generation = ?", [data, name, generation])
+                rewritten += 1
+            }
+        }
+        if encrypt && rewritten > 0 {
+            try execute("VACUUM")
+        }
+        return rewritten
+    }
+
+    // MARK: - 内部処理
+
+    private func exists(_ name: String) throws -> Bool {
+        return try !query("SELECT 1 FROM layouts WHERE name = ?", [name]) { _ in true }.isEmpty
+    }
+
+    private static let encryptedPrefix = "enc:"
+
+    private func encode(_ layout: Layout, encrypt: Bool) throws -> String {
+        if encrypt {
+            return Self.encryptedPrefix + (try LayoutCrypto.encode(layout, encrypt: true)).base64EncodedString()
+        }
+        let encoder = JSONEncoder()
+        encoder.outputFormatting = [.sortedKeys]
+        return String(decoding: try encoder.encode(layout), as: UTF8.self)
+    }
+
+    // 保存されている値を encrypt に合わせた値にする（既に合っていればそのまま）
+    private func converted(_ text: String, name: String, encrypt: Bool) throws -> String {
+        guard text.hasPrefix(Self.encryptedPrefix) != encrypt else { return text }
+        return try encode(decode(text, name: name), encrypt: encrypt)
+    }
+
+    private func decode(_ text: String, name: String) throws -> Layout {
+        let data: Data
+        if text.hasPrefix(Self.encryptedPrefix) {
+            guard let sealed = Data(base64Encoded: String(text.dropFirst(Self.encryptedPrefix.count))) else {
+                throw LayoutCrypto.CryptoError.malformed
+            }
+            data = sealed
+        } else {
+            data = Data(text.utf8)
+        }
+        var layout = try LayoutCrypto.decode(data)
+        if layout.name.isEmpty { layout.name = name }
+        return layout
+    }
+
+    // 初回アクセス時にデータベースを開いてテーブルを作成
+    private func connection() throws -> OpaquePointer {
+        if let db = db { return db }
+        try FileHelper.ensureDirectories()
+        var handle: OpaquePointer?
+        guard sqlite3_open(url.path, &handle) == SQLITE_OK, let opened = handle else {
+            let message = handle.map { String(cString: sqlite3_errmsg($0)) } ?? "unknown"
+            sqlite3_close(handle)
+            throw SQLiteError.openFailed(message)
+        }
+        db = opened
+        sqlite3_busy_timeout(opened, 2000)
+        try execute("""
+        CREATE TABLE IF NOT EXISTS layouts (
+            name TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS layout_backups (
+            name TEXT NOT NULL,
+            generation INTEGER NOT NULL,
+            data TEXT NOT NULL,
+            PRIMARY KEY (name, generation)
+        );
+        """)
+        return opened
+    }
+
+    private func execute(_ sql: String, _ params: [Any] = []) throws {
+        if params.isEmpty {
+            lock.lock()
+            defer { lock.unlock() }
+            let db = try connection()
+            guard sqlite3_exec(db, sql, nil, nil, nil) == SQLITE_OK else {
+                throw SQLiteError.statementFailed(String(cString: sqlite3_errmsg(db)))
+            }
+            return
+        }
+        _ = try query(sql, params) { _ in () }
+    }
+
+    private func query<T>(_ sql: String, _ params: [Any] = [], row: (OpaquePointer) -> T) throws -> [T] {
+        lock.lock()
+        defer { lock.unlock() }
+        let db = try connection()
+        var statement: OpaquePointer?
+        guard sqlite3_prepare_v2(db, sql, -1, &statement, nil) == SQLITE_OK, let stmt = statement else {
+            throw SQLiteError.statementFailed(String(cString: sqlite3_errmsg(db)))
+        }
+        defer { sqlite3_finalize(stmt) }
+        for (i, param) in params.enumerated() {
+            let index = Int32(i + 1)
+            switch param {
+            case let value as Int: sqlite3_bind_int64(stmt, index, Int64(value))
+            case let value as String: sqlite3_bind_text(stmt, index, value, -1, Self.transient)
+            default: sqlite3_bind_null(stmt, index)
+            }
+        }
+        var rows: [T] = []
+        while true {
+            switch sqlite3_step(stmt) {
+            case SQLITE_ROW: rows.append(row(stmt))
+            case SQLITE_DONE: return rows
+            default: throw SQLiteError.statementFailed(String(cString: sqlite3_errmsg(db)))
+            }
+        }
+    }
+
+    // バインドした文字列を SQLite 側でコピーさせる
+    private static let transient = unsafeBitCast(-1, to: sqlite3_destructor_type.self)
+
+    private static func text(_ stmt: OpaquePointer, _ column: Int32) -> String {
+        guard let cString = sqlite3_column_text(stmt, column) else { return "" }
+        return String(cString: cString)
+    }
+}
@@ -0,0 +1,94 @@
+import Foundation
+import CoreGraphics
+
+/// ディスプレイに対する比率でウィンドウ位置を表したレイアウト（`<base>/templates/<name>.json`）
+/// - 27インチで保存したレイアウトを 14インチのノートでも同じ比率で並べられるようにする
+/// - 比率はディスプレイ全体のフレームに対する値（x=0.5, width=0.5 なら右半分）
+struct LayoutTemplate: Codable {
+    struct Entry: Codable {
+        /// 元のウィンドウ情報（bounds は保存時の値で、当てはめ時に置き換える）
+        var window: WindowInfo
+        /// 保存時のディスプレイの並び順（0 = メイン、以降は左上から）
+        var displayIndex: Int
+        var displayUUID: String?
+        /// 保存時のディスプレイの位置（"leftmost" など。UUID のディスプレイが無い場合に使う）
+        var displayPosition: DisplayPosition?
+        /// ディスプレイに対する比率で表したフレーム
+        var relativeFrame: CGRect
+
+        enum CodingKeys: String, CodingKey {
+            case window
+            case displayIndex = "display_index"
+            case displayUUID = "display_uuid"
+            case displayPosition = "display_position"
+            case relativeFrame = "relative_frame"
+        }
+    }
+
+    var name: String
+    var entries: [Entry]
+
+    // MARK: - 変換
+
+    /// 絶対座標のレイアウトから作成
+    /// - ウィンドウごとに保存時のディスプレイ（記録が無ければ現在重なっているディスプレイ）を基準にする
+    init(layout: Layout) {
+        let saved = LayoutTemplate.ordered(layout.displays ?? DisplayManager.currentDisplays())
+        self.name = layout.name
+        self.entries = layout.windows.compactMap { w in
+            let display = w.displayUUID.flatMap { uuid in saved.first { $0.uuid == uuid } }
+                ?? w.displayBounds.flatMap { bounds in saved.first { $0.bounds == bounds } }
+                ?? saved.max { $0.bounds.intersection(w.bounds).area < $1.bounds.intersection(w.bounds).area }
+            guard let display = display, let relative = DisplayManager.normalized(w.bounds, in: display.bounds) else { return nil }
+            return Entry(window: w,
+                         displayIndex: saved.firstIndex(of: display) ?? 0,
+                         displayUUID: display.uuid,
+                         displayPosition: LayoutTemplate.position(of: display, in: saved),
+                         relativeFrame: relative)
+        }
+    }
+
+    /// 指定のディスプレイ構成に当てはめた絶対座標のレイアウト
+    /// - 同じディスプレイ（UUID）が接続されていればそこへ、無ければ同じ位置（最も左など）のディスプレイ、
+    ///   次に同じ並び順のディスプレイ、それも無ければメインへ配置
+    func instantiate(displays: [DisplayInfo] = DisplayManager.currentDisplays()) -> Layout {
+        let targets = LayoutTemplate.ordered(displays)
+        let windows = entries.compactMap { entry -> WindowInfo? in
+            let display = entry.displayUUID.flatMap { uuid in targets.first { $0.uuid == uuid } }
+                ?? entry.displayPosition.flatMap { DisplayManager.display(at: $0, in: targets) }
+                ?? (entry.displayIndex < targets.count ? targets[entry.displayIndex] : targets.first)
+            guard let display = display else { return nil }
+            var w = entry.window
+            w.bounds = DisplayManager.denormalized(entry.relativeFrame, in: display.bounds)
+            w.displayUUID = display.uuid
+            w.displayBounds = display.bounds
+            return w
+        }
+        var layout = Layout(name: name, windows: windows)
+        layout.displays = displays
+        return layout
+    }
+
+    // 左右の位置を優先して記録する（1台のみ、または中央のディスプレイはメインかどうかで表す）
+    private static func position(of display: DisplayInfo, in displays: [DisplayInfo]) -> DisplayPosition? {
+        guard displays.count > 1 else { return .main }
+        let positions = DisplayManager.placement(of: display.uuid, in: displays)?.positions ?? []
+        return [.leftmost, .rightmost, .main].first { positions.contains($0) }
+    }
+
+    // メインを先頭に、残りは左上から順に並べる
+    private static func ordered(_ displays: [DisplayInfo]) -> [DisplayInfo] {
+        return displays.sorted { a, b in
+            if a.isMain != b.isMain { return a.isMain }
+            if a.bounds.minX != b.bounds.minX { return a.bounds.minX < b.bounds.minX }
+            return a.bounds.minY < b.bounds.minY
+        }
+    }
+}
+
+private extension CGRect {
+    // 空の矩形は 0
+    var area: CGFloat {
+        return isNull ? 0 : width * height
+    }
+}
@@ -0,0 +1,58 @@
+import Foundation
+
+/// レイアウトの最終使用日時の記録（`<base>/layout_usage.json`、レイアウト名 → 日時）
+/// - レイアウトファイルとは別に端末ローカルに保存する
+///   （復元のたびにレイアウトを書き換えると、暗号化・同期のやり直しと更新日時の変化で同期競合の判定を誤るため）
+enum LayoutUsageIndex {
+
+    private static let lock = NSLock()
+
+    /// 最後に復元した日時（記録が無ければ nil）
+    static func lastUsed(_ name: String) -> Date? {
+        lock.lock()
+        defer { lock.unlock() }
+        return read()[name]
+    }
+
+    static func markUsed(_ name: String, at date: Date = Date()) {
+        modify { $0[name] = date }
+    }
+
+    /// レイアウト名の変更に合わせて記録を引き継ぐ
+    static func rename(from oldName: String, to newName: String) {
+        modify { index in
+            if let date = index.removeValue(forKey: oldName) { index[newName] = date }
+        }
+    }
+
+    static func remove(_ name: String) {
+        modify { $0.removeValue(forKey: name) }
+    }
+
+    // MARK: - 内部
+
+    private static func modify(_ body: (inout [String: Date]) -> Void) {
+        lock.lock()
+        defer { lock.unlock() }
+        var index = read()
+        body(&index)
+        do {
+            let url = FileHelper.layoutUsageURL()
+            let encoder = JSONEncoder()
+            encoder.dateEncodingStrategy = .iso8601
+            encoder.outputFormatting = [.prettyPrinted, .sortedKeys]
+            try FileHelper.ensureParentDirectory(of: url)
+            try encoder.encode(index).write(to: url, options: [.atomic])
+        } catch {
+            Log.warning("[layout] 最終使用日時の記録に失敗: \(error.localizedDescription)")
+        }
+    }
+
+    // lock を保持した状態で呼ぶ（読めない場合は空）
+    private static func read() -> [String: Date] {
+        guard let data = try? Data(contentsOf: FileHelper.layoutUsageURL()) else { return [:] }
+        let decoder = JSONDecoder()
+        decoder.dateDecodingStrategy = .iso8601
+        return (try? decoder.decode([String: Date].self, from: data)) ?? [:]
+    }
+}
@@ -0,0 +1,115 @@
+import Foundation
+
+/// ログの重要度
+enum LogLevel: String, Codable, Comparable, CaseIterable {
+    case debug
+    case info
+    case warning
+    case error
+
+    static func < (lhs: LogLevel, rhs: LogLevel) -> Bool {
+        return allCases.firstIndex(of: lhs)! < allCases.firstIndex(of: rhs)!
+    }
+}
+
+/// 診断用ログ（標準出力と、設定されていればファイルに書き出す）
+/// - レベル・出力先・ローテーションは Config の log_* 項目（configure(with:) で反映）
+/// - ファイルには日時とレベルを付けて追記し、log_max_size_kb を超えたら <file>.1 以降へローテーションする
+enum Log {
+    private static let queue = DispatchQueue(label: "window_restore.log")
+    private static var level: LogLevel = .info
+    private static var fileURL: URL?
+    private static var maxSizeBytes: UInt64 = 0
+    private static var maxFiles = 0
+    private static var handle: FileHandle?
+
+    private static let dateFormatter: ISO8601DateFormatter = {
+        let formatter = ISO8601DateFormatter()
+        formatter.formatOptions = [.withInternetDateTime, .withFractionalSeconds]
+        return formatter
+    }()
+
+    /// 設定を反映（起動時と設定ファイルの変更時に呼ぶ）
+    static func configure(with config: Config) {
+        let url = config.logFile.flatMap { path -> URL? in
+            let expanded = (path.trimmingCharacters(in: .whitespacesAndNewlines) as NSString).expandingTildeInPath
+            return expanded.isEmpty ? nil : URL(fileURLWithPath: expanded)
+        }
+        queue.sync {
+            level = config.logLevel
+            maxSizeBytes = config.logMaxSizeKb * 1024
+            maxFiles = config.logMaxFiles
+            if url != fileURL {
+                try? handle?.close()
+                handle = nil
+                fileURL = url
+            }
+        }
+    }
+
+    static func debug(_ message: @autoclosure () -> String) {
+        write(.debug, message)
+    }
+
+    static func info(_ message: @autoclosure () -> String) {
+        write(.info, message)
+    }
+
+    static func warning(_ message: @autoclosure () -> String) {
+        write(.warning, message)
+    }
+
+    static func error(_ message: @autoclosure () -> String) {
+        write(.error, message)
+    }
+
+    /// 現在のログファイル（設定されていない場合は nil）
+    static var currentFileURL: URL? {
+        return queue.sync { fileURL }
+    }
+
+    // MARK: - 書き出し
+
+    private static func write(_ messageLevel: LogLevel, _ message: () -> String) {
+        guard queue.sync(execute: { messageLevel >= level }) else { return }
+        let text = message()
+        print(text)
+        let line = "\(dateFormatter.string(from: Date())) [\(messageLevel.rawValue.uppercased())] \(text)\n"
+        queue.async {
+            guard let handle = openFile() else { return }
+            handle.seekToEndOfFile()
+            handle.write(Data(line.utf8))
+            rotateIfNeeded(handle)
+        }
+    }
+
+    // queue 上で呼ぶ
+    private static func openFile() -> FileHandle? {
+        if let handle = handle { return handle }
+        guard let url = fileURL else { return nil }
+        do {
+            try FileHelper.ensureParentDirectory(of: url)
+            if !FileManager.default.fileExists(atPath: url.path) {
+                FileManager.default.createFile(atPath: url.path, contents: nil)
+            }
+            handle = try FileHandle(forWritingTo: url)
+        } catch {
+            print("[log] ログファイルを開けません \(url.path): \(error.localizedDescription)")
+            fileURL = nil
+        }
+        return handle
+    }
+
+    // queue 上で呼ぶ
+    private static func rotateIfNeeded(_ current: FileHandle) {
+        guard maxSizeBytes > 0, let url = fileURL, current.offsetInFile >= maxSizeBytes else { return }
+        do {
+            if maxFiles > 0 {
+                try FileHelper.rotateBackups(of: url, keep: maxFiles)
+            }
+            current.truncateFile(atOffset: 0)
+        } catch {
+            print("[log] ログファイルのローテーションに失敗: \(error.localizedDescription)")
+        }
+    }
+}
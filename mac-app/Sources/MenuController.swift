@@ -16,6 +16,9 @@ protocol MenuControllerDelegate: AnyObject {
     /// 引数: name - 復元するレイアウト名
     func restoreLayout(name: String)
     
+    /// 設定の既定のレイアウトを復元
+    func restoreDefaultLayout()
+    
     /// レイアウトを削除
     /// 引数: name - 削除するレイアウト名
     func deleteLayout(name: String)
@@ -79,6 +82,7 @@ class MenuController {
         // メニュー項目を追加
         addSaveLayoutMenuItem()
         addRestoreLayoutMenuItem()
+        addRestoreDefaultMenuItem()
         addLayoutListMenuItem()
         addSeparatorMenuItem()
         addSettingsMenuItem()
@@ -110,6 +114,16 @@ class MenuController {
         mainMenu?.addItem(restoreItem)
     }
     
+    /// 既定レイアウト復元メニュー項目の追加
+    /// 設定の default_layout を名前を選ばずに復元するメニュー項目を作成
+    private func addRestoreDefaultMenuItem() {
+        let restoreDefaultItem = NSMenuItem(title: "⏪ 既定のレイアウトを復元", action: #selector(restoreDefaultLayout), keyEquivalent: "")
+        restoreDefaultItem.target = self
+        restoreDefaultItem.toolTip = "設定の既定のレイアウト（default_layout）を復元します"
+        
+        mainMenu?.addItem(restoreDefaultItem)
+    }
+    
     /// レイアウト一覧メニュー項目の追加
     /// 保存されたレイアウトの一覧を表示するメニュー項目を作成
     private func addLayoutListMenuItem() {
@@ -291,6 +305,11 @@ class MenuController {
         delegate?.restoreLayout(name: layoutName)
     }
     
+    /// 既定のレイアウトを復元
+    @objc private func restoreDefaultLayout() {
+        delegate?.restoreDefaultLayout()
+    }
+    
     /// レイアウトを削除
     /// 引数: sender - メニュー項目
     @objc private func deleteLayout(_ sender: NSMenuItem) {
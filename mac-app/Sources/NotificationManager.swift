@@ -0,0 +1,205 @@
+import Foundation
+import UserNotifications
+
+/// ユーザー通知（通知センター）の送信
+/// - .app バンドルとして実行されている場合は UserNotifications で送信する
+/// - バンドル外（swift run などで実行ファイルを直接起動）では UNUserNotificationCenter が使えないため osascript で送信する
+enum NotificationManager {
+
+    /// UserNotifications を使えるか（bundle id を持つ .app として実行されているか）
+    static var usesNativeNotifications: Bool {
+        return Bundle.main.bundleIdentifier != nil && Bundle.main.bundleURL.pathExtension == "app"
+    }
+
+    /// 通知の許可を要求（アプリ起動時に1回呼ぶ）
+    /// - 集中モード中の通知を抑制/保留する設定の場合は、集中モードの状態の共有も要求する
+    ///   （Info.plist の NSFocusStatusUsageDescription が必要なため、.app として実行されている場合のみ）
+    static func requestPermission() {
+        guard usesNativeNotifications else {
+            Log.info("[notify] バンドル外で実行されているため osascript で通知します")
+            return
+        }
+        if Config.load().notificationsDuringFocus != .deliver {
+            FocusStatus.requestAuthorization()
+        }
+        UNUserNotificationCenter.current().requestAuthorization(options: [.alert, .sound]) { granted, error in
+            if let error = error {
+                Log.warning("[notify] 通知の許可の要求に失敗: \(error)")
+            } else {
+                Log.debug("[notify] 通知の許可: \(granted)")
+            }
+        }
+    }
+
+    /// 通知を送信
+    /// - sound: 警告など注意を引きたい通知では true
+    /// - identifier: 同じ identifier の表示中の通知を置き換える（osascript では置き換えられず新しく表示される）
+    /// - 同じ内容の通知の連続（Config.notificationDedupSeconds）と件数の上限（Config.notificationMaxPerMinute）は
+    ///   identifier を指定しない通知にのみ適用する（進捗の置き換えは間引かない）
+    ///   警告（sound: true）は件数の上限の対象外で、同じ内容の連続のみまとめる
+    /// - 集中モード中は Config.notificationsDuringFocus に従い、音の無い通知を表示しない/終了後に表示する
+    static func post(title: String, body: String, sound: Bool = false, identifier: String? = nil) {
+        let config = Config.load()
+        if !sound && config.notificationsDuringFocus != .deliver && FocusStatus.isActive {
+            if config.notificationsDuringFocus == .queue {
+                enqueueForFocus(PendingNotification(title: title, body: body, identifier: identifier))
+            } else {
+                Log.debug("[notify] 集中モード中のため通知しません: \(title)")
+            }
+            return
+        }
+        deliver(title: title, body: body, sound: sound, identifier: identifier, config: config)
+    }
+
+    private static func deliver(title: String, body: String, sound: Bool, identifier: String?, config: Config) {
+        var body = body
+        if identifier == nil {
+            switch throttle(title: title, body: body, sound: sound, config: config) {
+            case .suppress:
+                return
+            case .post(let collapsed):
+                if collapsed > 0 { body += "（ほか同じ通知 \(collapsed) 件）" }
+            }
+        }
+        guard usesNativeNotifications else {
+            postWithAppleScript(title: title, body: body, sound: sound)
+            return
+        }
+        let content = UNMutableNotificationContent()
+        content.title = title
+        content.body = body
+        if sound { content.sound = .default }
+        let request = UNNotificationRequest(identifier: identifier ?? UUID().uuidString, content: content, trigger: nil)
+        UNUserNotificationCenter.current().add(request) { error in
+            if let error = error {
+                Log.warning("[notify] 通知の送信に失敗: \(error)")
+            }
+        }
+    }
+
+    /// 警告を通知（音付き）
+    static func warn(title: String, body: String) {
+        post(title: title, body: body, sound: true)
+    }
+
+    /// インストールされていないため起動できなかったアプリを通知
+    static func warnMissingApps(_ apps: [RestoreReport.MissingApp]) {
+        guard !apps.isEmpty else { return }
+        let names = apps.map { $0.name.isEmpty ? $0.bundleId : $0.name }
+        warn(title: "起動できないアプリがあります",
+             body: "インストールされていないため復元できませんでした: \(names.joined(separator: ", "))")
+    }
+
+    // MARK: - 集中モード
+
+    private struct PendingNotification {
+        let title: String
+        let body: String
+        let identifier: String?
+    }
+
+    // 集中モードの終了を確認する間隔（秒）
+    private static let focusPollInterval: TimeInterval = 30
+    // 上限を超えた場合は古いものから捨てる
+    private static let maxPendingNotifications = 50
+
+    private static let focusLock = NSLock()
+    private static var pending: [PendingNotification] = []
+    private static var focusTimer: Timer?
+
+    // 同じ identifier の保留中の通知は置き換える（進捗は最後の状態だけを残す）
+    private static func enqueueForFocus(_ notification: PendingNotification) {
+        focusLock.lock()
+        if let identifier = notification.identifier, let index = pending.firstIndex(where: { $0.identifier == identifier }) {
+            pending[index] = notification
+        } else {
+            pending.append(notification)
+            if pending.count > maxPendingNotifications { pending.removeFirst(pending.count - maxPendingNotifications) }
+        }
+        focusLock.unlock()
+        Log.debug("[notify] 集中モードの終了後に通知します: \(notification.title)")
+        DispatchQueue.main.async {
+            guard focusTimer == nil else { return }
+            focusTimer = Timer.scheduledTimer(withTimeInterval: focusPollInterval, repeats: true) { _ in
+                flushPendingIfFocusEnded()
+            }
+        }
+    }
+
+    // メインスレッドで呼ぶ
+    private static func flushPendingIfFocusEnded() {
+        guard !FocusStatus.isActive else { return }
+        focusTimer?.invalidate()
+        focusTimer = nil
+        focusLock.lock()
+        let queued = pending
+        pending.removeAll()
+        focusLock.unlock()
+        guard !queued.isEmpty else { return }
+        Log.info("[notify] 集中モード中に保留した通知を表示します: \(queued.count)件")
+        let config = Config.load()
+        for notification in queued {
+            deliver(title: notification.title, body: notification.body, sound: false,
+                    identifier: notification.identifier, config: config)
+        }
+    }
+
+    // MARK: - 間引き
+
+    private enum ThrottleDecision {
+        /// 表示する（collapsed: 前回表示してからまとめた同じ内容の通知の件数）
+        case post(collapsed: Int)
+        case suppress
+    }
+
+    private static let throttleLock = NSLock()
+    // 内容（タイトルと本文）ごとの最後に表示した時刻と、その後まとめた件数
+    private static var recent: [String: (postedAt: Date, collapsed: Int)] = [:]
+    // 直近1分間に表示した時刻
+    private static var postedTimes: [Date] = []
+
+    private static func throttle(title: String, body: String, sound: Bool, config: Config, now: Date = Date()) -> ThrottleDecision {
+        throttleLock.lock()
+        defer { throttleLock.unlock() }
+        let key = title + "\n" + body
+        let window = TimeInterval(config.notificationDedupSeconds)
+        recent = recent.filter { now.timeIntervalSince($0.value.postedAt) < max(window, 60) }
+        if window > 0, let last = recent[key], now.timeIntervalSince(last.postedAt) < window {
+            recent[key] = (last.postedAt, last.collapsed + 1)
+            Log.debug("[notify] 同じ内容の通知をまとめました: \(title)")
+            return .suppress
+        }
+        postedTimes = postedTimes.filter { now.timeIntervalSince($0) < 60 }
+        // 警告は件数の上限を超えていても表示する（表示した件数には含める）
+        if !sound && config.notificationMaxPerMinute > 0 && postedTimes.count >= config.notificationMaxPerMinute {
+            Log.info("[notify] 通知が多すぎるため表示しません: \(title)")
+            return .suppress
+        }
+        let collapsed = recent[key]?.collapsed ?? 0
+        recent[key] = (now, 0)
+        postedTimes.append(now)
+        return .post(collapsed: collapsed)
+    }
+
+    // MARK: - osascript
+
+    // `display notification` で送信（完了を待たない）
+    private static func postWithAppleScript(title: String, body: String, sound: Bool) {
+        var script = "display notification \"\(escaped(body))\" with title \"\(escaped(title))\""
+        if sound { script += " sound name \"default\"" }
+        let process = Process()
+        process.executableURL = URL(fileURLWithPath: "/usr/bin/osascript")
+        process.arguments = ["-e", script]
+        do {
+            try process.run()
+        } catch {
+            Log.warning("[notify] osascript による通知に失敗: \(error.localizedDescription)")
+        }
+    }
+
+    // AppleScript 文字列リテラル用のエスケープ
+    private static func escaped(_ text: String) -> String {
+        return text.replacingOccurrences(of: "\\", with: "\\\\")
+            .replacingOccurrences(of: "\"", with: "\\\"")
+    }
+}
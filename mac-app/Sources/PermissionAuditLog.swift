@@ -0,0 +1,127 @@
+import Foundation
+
+/// 権限の確認結果と要求の記録（`<base>/permission_audit.jsonl`、1行1件）
+/// - 「macOS のアップデート後に動かなくなった」といった問い合わせの調査用
+/// - 確認結果は前回の記録から変わった場合のみ記録する（頻繁な確認でファイルが膨らまないように）
+enum PermissionAuditLog {
+
+    enum Event: String, Codable {
+        /// 状態の確認（前回から変化した場合のみ）
+        case check
+        /// システムの許可ダイアログの表示
+        case prompt
+        /// システム設定のパネルを開いた
+        case openSettings = "open_settings"
+        /// 実行中の権限の取り消しを検知した
+        case revoked
+    }
+
+    struct Entry: Codable {
+        let date: Date
+        let event: Event
+        let permission: PermissionKind
+        /// 結果（"granted", "denied" や AutomationStatus の値）
+        let result: String
+        /// オートメーションの対象アプリ（bundle id）
+        var target: String?
+        /// 記録時の macOS のバージョン
+        let osVersion: String
+
+        enum CodingKeys: String, CodingKey {
+            case date, event, permission, result, target
+            case osVersion = "os_version"
+        }
+    }
+
+    // 超えたら古い記録を削除する上限
+    private static let maxEntries = 1000
+
+    private static let lock = NSLock()
+    // 権限（と対象）ごとの前回記録した確認結果
+    private static var lastResults: [String: String] = [:]
+
+    // MARK: - 記録
+
+    /// 確認結果を記録（前回記録した結果と同じ場合は記録しない）
+    static func recordCheck(_ permission: PermissionKind, result: String, target: String? = nil) {
+        let key = [permission.rawValue, target].compactMap { $0 }.joined(separator: ":")
+        lock.lock()
+        let changed = lastResults[key] != result
+        lastResults[key] = result
+        lock.unlock()
+        if changed {
+            record(.check, permission: permission, result: result, target: target)
+        }
+    }
+
+    static func record(_ event: Event, permission: PermissionKind, result: String, target: String? = nil) {
+        let entry = Entry(date: Date(), event: event, permission: permission, result: result,
+                          target: target, osVersion: ProcessInfo.processInfo.operatingSystemVersionString)
+        lock.lock()
+        defer { lock.unlock() }
+        do {
+            let url = FileHelper.permissionAuditLogURL()
+            var line = try encoder.encode(entry)
+            line.append(0x0A)
+            try FileHelper.ensureParentDirectory(of: url)
+            if let handle = try? FileHandle(forWritingTo: url) {
+                defer { try? handle.close() }
+                try handle.seekToEnd()
+                try handle.write(contentsOf: line)
+            } else {
+                try line.write(to: url, options: [.atomic])
+            }
+            trimIfNeeded(url)
+        } catch {
+            Log.warning("[permission] 権限の記録に失敗: \(error.localizedDescription)")
+        }
+    }
+
+    // MARK: - 読み込み
+
+    /// 記録（古い順）。limit 指定時は新しいものから limit 件
+    static func entries(limit: Int? = nil) -> [Entry] {
+        lock.lock()
+        defer { lock.unlock() }
+        let all = readEntries(FileHelper.permissionAuditLogURL())
+        guard let limit = limit else { return all }
+        return Array(all.suffix(max(0, limit)))
+    }
+
+    static func clear() {
+        lock.lock()
+        defer { lock.unlock() }
+        try? FileManager.default.removeItem(at: FileHelper.permissionAuditLogURL())
+        lastResults.removeAll()
+    }
+
+    // MARK: - 内部
+
+    private static var encoder: JSONEncoder {
+        let encoder = JSONEncoder()
+        encoder.dateEncodingStrategy = .iso8601
+        encoder.outputFormatting = [.sortedKeys]
+        return encoder
+    }
+
+    // 読めない行は読み飛ばす
+    private static func readEntries(_ url: URL) -> [Entry] {
+        guard let text = try? String(contentsOf: url, encoding: .utf8) else { return [] }
+        let decoder = JSONDecoder()
+        decoder.dateDecodingStrategy = .iso8601
+        return text.split(separator: "\n").compactMap { try? decoder.decode(Entry.self, from: Data($0.utf8)) }
+    }
+
+    // lock を保持した状態で呼ぶ
+    private static func trimIfNeeded(_ url: URL) {
+        let entries = readEntries(url)
+        guard entries.count > maxEntries else { return }
+        let kept = entries.suffix(maxEntries / 2).compactMap { try? encoder.encode($0) }
+        var data = Data()
+        for line in kept {
+            data.append(line)
+            data.append(0x0A)
+        }
+        try? data.write(to: url, options: [.atomic])
+    }
+}
@@ -0,0 +1,275 @@
+import Cocoa
+import Foundation
+import ApplicationServices
+import CoreServices
+
+/// オートメーション（Apple Events）権限の状態
+enum AutomationStatus: String, Codable {
+    case granted
+    case denied
+    /// まだ許可/拒否が選ばれていない（送信時に確認ダイアログが出る）
+    case notDetermined = "not_determined"
+    /// 対象アプリが起動していないため判定できない
+    case targetNotRunning = "target_not_running"
+    case unknown
+}
+
+/// アプリが利用する権限の種類
+enum PermissionKind: String, Codable, CaseIterable {
+    case accessibility
+    case screenRecording = "screen_recording"
+    case automation
+
+    /// システム設定の該当パネルを直接開く URL
+    var settingsURL: URL {
+        let anchor: String
+        switch self {
+        case .accessibility: anchor = "Privacy_Accessibility"
+        case .screenRecording: anchor = "Privacy_ScreenCapture"
+        case .automation: anchor = "Privacy_Automation"
+        }
+        return URL(string: "x-apple.systempreferences:com.apple.preference.security?\(anchor)")!
+    }
+
+    /// 表示名
+    var displayName: String {
+        switch self {
+        case .accessibility: return "アクセシビリティ"
+        case .screenRecording: return "画面収録"
+        case .automation: return "オートメーション"
+        }
+    }
+}
+
+/// ウィンドウの配置方法
+enum RestoreBackend: String, Codable, CaseIterable {
+    /// Accessibility API（AXUIElement）
+    case accessibility
+    /// AppleScript（System Events）。AX で配置できなかったウィンドウのフォールバック
+    case appleScript = "applescript"
+
+    /// この配置方法で必要な権限
+    /// - 画面収録はどちらにも不要（AX が使えない場合の保存時のタイトル取得にのみ使う）
+    var requiredPermissions: [PermissionKind] {
+        switch self {
+        case .accessibility: return [.accessibility]
+        // System Events によるウィンドウ操作にはアクセシビリティ権限も必要
+        case .appleScript: return [.accessibility, .automation]
+        }
+    }
+}
+
+/// 権限ごとの現在の状態（オンボーディング画面で不足している項目を示すため個別に持つ）
+struct PermissionStatus: Codable, Equatable {
+    var accessibility: Bool
+    var screenRecording: Bool
+    /// System Events へのオートメーション権限
+    var automation: AutomationStatus
+    /// 現在使われる配置方法
+    var backends: [RestoreBackend]
+    /// backends で必要な権限
+    var required: [PermissionKind]
+
+    enum CodingKeys: String, CodingKey {
+        case accessibility
+        case screenRecording = "screen_recording"
+        case automation
+        case backends
+        case required
+    }
+
+    /// ウィンドウの保存/復元に最低限必要な権限（アクセシビリティ）があるか
+    var canRestore: Bool {
+        return accessibility
+    }
+
+    /// 必要な権限のうち許可されていないもの
+    /// - System Events を起動できずオートメーションを判定できない場合は不足として扱わない
+    var missing: [PermissionKind] {
+        return required.filter { !isGranted($0) && !($0 == .automation && automation == .targetNotRunning) }
+    }
+
+    func isGranted(_ kind: PermissionKind) -> Bool {
+        switch kind {
+        case .accessibility: return accessibility
+        case .screenRecording: return screenRecording
+        case .automation: return automation == .granted
+        }
+    }
+}
+
+/// 処理に必要な権限が許可されていない
+struct PermissionDeniedError: LocalizedError {
+    /// 不足している権限
+    let missing: [PermissionKind]
+
+    var errorDescription: String? {
+        let names = missing.map { $0.displayName }.joined(separator: "、")
+        return "\(names)権限が必要です（システム設定 > プライバシーとセキュリティ）"
+    }
+}
+
+/// macOS のプライバシー権限（TCC）の状態確認
+/// - prompt を指定しない限りダイアログは出さず、現在の状態のみを返す
+enum PermissionChecker {
+
+    /// すべての権限の現在の状態（ダイアログは出さない）
+    static func currentStatus() -> PermissionStatus {
+        let automation = checkSystemEventsAutomation()
+        let backends = activeBackends(automation: automation)
+        return PermissionStatus(accessibility: checkAccessibility(),
+                                screenRecording: checkScreenRecording(),
+                                automation: automation,
+                                backends: backends,
+                                required: requiredPermissions(for: backends))
+    }
+
+    /// 現在使われる配置方法
+    /// - AX は常に使う。AppleScript はオートメーションが拒否されていない場合のみフォールバックとして使う
+    static func activeBackends(automation: AutomationStatus? = nil) -> [RestoreBackend] {
+        let automation = automation ?? checkSystemEventsAutomation()
+        return automation == .denied ? [.accessibility] : [.accessibility, .appleScript]
+    }
+
+    /// 配置方法で必要な権限（重複を除き PermissionKind の定義順）
+    static func requiredPermissions(for backends: [RestoreBackend]) -> [PermissionKind] {
+        let required = Set(backends.flatMap { $0.requiredPermissions })
+        return PermissionKind.allCases.filter { required.contains($0) }
+    }
+
+    /// 指定の権限が許可されているか（設定画面を開いた後の確認用。ダイアログは出さない）
+    /// - 画面収録は許可後もアプリを再起動するまで false のままの場合がある
+    static func isGranted(_ kind: PermissionKind) -> Bool {
+        switch kind {
+        case .accessibility: return checkAccessibility()
+        case .screenRecording: return checkScreenRecording()
+        case .automation: return checkSystemEventsAutomation() == .granted
+        }
+    }
+
+    /// システム設定で指定の権限のパネルを開く
+    /// 戻り値: 開けた場合 true
+    @discardableResult
+    static func openSettings(for kind: PermissionKind) -> Bool {
+        Log.info("[permission] システム設定を開きます: \(kind.displayName)")
+        let opened = NSWorkspace.shared.open(kind.settingsURL)
+        PermissionAuditLog.record(.openSettings, permission: kind, result: opened ? "opened" : "failed")
+        return opened
+    }
+
+    // MARK: - アクセシビリティ
+
+    /// アクセシビリティ権限があるか
+    /// - prompt: true の場合、未許可ならシステムの許可ダイアログを表示する（戻り値は表示前の状態）
+    static func checkAccessibility(prompt: Bool = false) -> Bool {
+        guard prompt else {
+            let trusted = AXIsProcessTrusted()
+            PermissionAuditLog.recordCheck(.accessibility, result: trusted ? "granted" : "denied")
+            return trusted
+        }
+        let options = [kAXTrustedCheckOptionPrompt.takeUnretainedValue() as String: true] as CFDictionary
+        let trusted = AXIsProcessTrustedWithOptions(options)
+        PermissionAuditLog.record(.prompt, permission: .accessibility, result: trusted ? "granted" : "denied")
+        return trusted
+    }
+
+    // MARK: - 画面収録
+
+    /// 画面収録権限があるか（無い場合 CGWindowList から他アプリのウィンドウタイトルを取得できない）
+    /// - prompt: true の場合、未許可ならシステムの許可ダイアログを表示する（許可は次回起動時から有効）
+    static func checkScreenRecording(prompt: Bool = false) -> Bool {
+        let granted = CGPreflightScreenCaptureAccess()
+        PermissionAuditLog.recordCheck(.screenRecording, result: granted ? "granted" : "denied")
+        guard !granted && prompt else { return granted }
+        let requested = CGRequestScreenCaptureAccess()
+        PermissionAuditLog.record(.prompt, permission: .screenRecording, result: requested ? "granted" : "denied")
+        return requested
+    }
+
+    // MARK: - オートメーション
+
+    /// AppleScript による配置で操作する System Events
+    static let systemEventsBundleId = "com.apple.systemevents"
+
+    /// 指定アプリへの Apple Events 送信（オートメーション）権限の状態
+    /// - prompt: true の場合、未確認なら確認ダイアログを表示する（応答まで待機するためメインスレッドでは呼ばない）
+    static func checkAutomation(bundleId: String = systemEventsBundleId, prompt: Bool = false) -> AutomationStatus {
+        var target = AEAddressDesc()
+        let created = bundleId.utf8CString.withUnsafeBufferPointer { buffer in
+            AECreateDesc(DescType(typeApplicationBundleID), buffer.baseAddress, buffer.count - 1, &target)
+        }
+        guard created == OSErr(noErr) else { return .unknown }
+        defer { AEDisposeDesc(&target) }
+        let status = AEDeterminePermissionToAutomateTarget(&target, AEEventClass(typeWildCard), AEEventID(typeWildCard), prompt)
+        let result: AutomationStatus
+        switch status {
+        case OSStatus(noErr): result = .granted
+        case OSStatus(errAEEventNotPermitted): result = .denied
+        case OSStatus(errAEEventWouldRequireUserConsent): result = .notDetermined
+        case OSStatus(procNotFound): result = .targetNotRunning
+        default:
+            Log.debug("[permission] オートメーション権限を判定できません \(bundleId): \(status)")
+            result = .unknown
+        }
+        if prompt {
+            PermissionAuditLog.record(.prompt, permission: .automation, result: result.rawValue, target: bundleId)
+        } else {
+            PermissionAuditLog.recordCheck(.automation, result: result.rawValue, target: bundleId)
+        }
+        return result
+    }
+
+    /// System Events へのオートメーション権限の状態（起動していなければ起動を要求する）
+    /// - 起動していないと判定できないため、起動が完了するまでは targetNotRunning を返す（次の確認で判定される）
+    /// - prompt: true の場合はメインスレッド以外から呼ばれるため、ダイアログを出せるよう起動の完了を待ってから確認する
+    static func checkSystemEventsAutomation(prompt: Bool = false) -> AutomationStatus {
+        if !launchSystemEventsIfNeeded() {
+            guard prompt, !Thread.isMainThread, waitForSystemEvents() else { return .targetNotRunning }
+        }
+        return checkAutomation(bundleId: systemEventsBundleId, prompt: prompt)
+    }
+
+    // System Events の起動を要求済みか（起動中に確認が繰り返されても起動の要求は1回のみ）
+    private static var systemEventsLaunchRequested = false
+    private static let systemEventsLaunchLock = NSLock()
+
+    /// System Events が起動していなければ起動を要求する（完了は待たない）
+    /// - バックグラウンド専用アプリのため AppLauncher.launchAll（エージェントアプリは起動しない）は使わない
+    /// - 起動を確認できた後に終了した場合は、次の呼び出しで再び起動を要求する
+    /// 戻り値: 起動が完了している場合 true
+    @discardableResult
+    static func launchSystemEventsIfNeeded() -> Bool {
+        let running = AppLauncher.runningApplication(bundleId: systemEventsBundleId)?.isFinishedLaunching == true
+        systemEventsLaunchLock.lock()
+        defer { systemEventsLaunchLock.unlock() }
+        if running {
+            systemEventsLaunchRequested = false
+            return true
+        }
+        guard !systemEventsLaunchRequested else { return false }
+        systemEventsLaunchRequested = true
+        if !AppLauncher.launch(bundleId: systemEventsBundleId) {
+            Log.warning("[permission] System Events を起動できないため、オートメーション権限を判定できません")
+        }
+        return false
+    }
+
+    // System Events の起動待機のタイムアウト（秒）
+    private static let systemEventsLaunchTimeout: TimeInterval = 3.0
+
+    // 起動の完了を待機（メインスレッド以外からのみ呼ぶ）
+    private static func waitForSystemEvents() -> Bool {
+        let deadline = Date().addingTimeInterval(systemEventsLaunchTimeout)
+        while Date() < deadline {
+            if AppLauncher.runningApplication(bundleId: systemEventsBundleId)?.isFinishedLaunching == true { return true }
+            Thread.sleep(forTimeInterval: 0.1)
+        }
+        return false
+    }
+
+    /// 複数アプリのオートメーション権限をまとめて確認
+    /// 戻り値: bundle id ごとの状態
+    static func checkAutomation(bundleIds: [String]) -> [String: AutomationStatus] {
+        return Dictionary(uniqueKeysWithValues: Set(bundleIds).map { ($0, checkAutomation(bundleId: $0)) })
+    }
+}
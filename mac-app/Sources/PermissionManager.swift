@@ -57,7 +57,7 @@ class PermissionManager {
     /// 戻り値: 権限がある場合true
     func checkAccessibilityPermission() -> Bool {
         print("アクセシビリティ権限をチェック中...")
-        let hasPermission = AXIsProcessTrusted()
+        let hasPermission = PermissionChecker.checkAccessibility()
         print("アクセシビリティ権限チェック結果: \(hasPermission)")
         return hasPermission
     }
@@ -126,13 +126,25 @@ class PermissionManager {
         print("アクセシビリティ設定を開く中...")
         
         // アクセシビリティ設定を開く
-        let url = URL(string: "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")!
-        
-        NSWorkspace.shared.open(url)
+        PermissionChecker.openSettings(for: .accessibility)
         
         print("アクセシビリティ設定が開かれました")
     }
     
+    /// 指定の権限の設定画面を開き、一定時間内に許可されたかを確認
+    /// 引数: kind - 権限の種類、timeout - 確認を続ける時間（秒）、completion - 許可されたかどうか（メインスレッドで呼ばれる）
+    func openSettingsAndVerify(_ kind: PermissionKind, timeout: TimeInterval = 60, completion: @escaping (Bool) -> Void) {
+        PermissionChecker.openSettings(for: kind)
+        let deadline = Date().addingTimeInterval(timeout)
+        Timer.scheduledTimer(withTimeInterval: 1.0, repeats: true) { timer in
+            let granted = PermissionChecker.isGranted(kind)
+            guard granted || Date() >= deadline else { return }
+            timer.invalidate()
+            Log.info("[permission] \(kind.displayName)の確認結果: \(granted ? "許可" : "未許可")")
+            completion(granted)
+        }
+    }
+    
     /// 権限要求ダイアログを表示
     /// ユーザーに権限の必要性を説明し、システム設定への誘導を行う
     func showPermissionRequestDialog() {
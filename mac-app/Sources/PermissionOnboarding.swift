@@ -0,0 +1,133 @@
+import Foundation
+
+/// 初回起動時の権限設定の流れ（確認 → 要求 → 設定画面を開く → 許可の確認）
+/// - 状態は JSON で保存でき、アプリと CLI のどちらからでも同じ流れを途中から再開できる
+/// - 不足している権限を1つずつ順に進める
+struct PermissionOnboarding: Codable, Equatable {
+
+    enum Step: String, Codable {
+        /// 権限の状態を確認する
+        case check
+        /// システムの許可ダイアログを表示する
+        case prompt
+        /// システム設定の該当パネルを開く
+        case openSettings = "open_settings"
+        /// 許可されたかを確認する
+        case verify
+        /// すべての権限を設定した（またはスキップした）
+        case completed
+    }
+
+    enum Event {
+        case checked(PermissionStatus)
+        case prompted
+        case settingsOpened
+        case verified(granted: Bool)
+        /// 現在の権限の設定を見送る
+        case skipped
+    }
+
+    private(set) var step: Step = .check
+    /// 設定が済んでいない権限（先頭が現在の対象）
+    private(set) var pending: [PermissionKind] = []
+    /// スキップした権限
+    private(set) var skipped: [PermissionKind] = []
+    /// 現在の権限で許可の確認に失敗した回数
+    private(set) var attempts = 0
+
+    /// 許可の確認にこの回数失敗した権限はスキップする（設定画面を開く → 確認を繰り返し続けないように）
+    static let maxAttempts = 3
+
+    /// 現在設定を進めている権限
+    var current: PermissionKind? {
+        return step == .completed ? nil : pending.first
+    }
+
+    var isCompleted: Bool {
+        return step == .completed
+    }
+
+    // MARK: - 状態遷移
+
+    /// イベントを受けて次のステップへ進める（現在のステップに対応しないイベントは無視する）
+    mutating func handle(_ event: Event) {
+        switch (step, event) {
+        case (.check, .checked(let status)):
+            // 使われる配置方法で必要な権限のみ（使わない権限は求めない）
+            pending = status.missing.filter { !skipped.contains($0) }
+            attempts = 0
+            step = pending.isEmpty ? .completed : .prompt
+        case (.prompt, .prompted):
+            step = .verify
+        case (.openSettings, .settingsOpened):
+            step = .verify
+        case (.verify, .verified(let granted)):
+            if granted {
+                moveToNext()
+            } else {
+                attempts += 1
+                if attempts >= Self.maxAttempts, let kind = pending.first {
+                    Log.warning("[permission] オンボーディング: \(kind.displayName)権限を \(attempts) 回確認できなかったためスキップします")
+                    skipped.append(kind)
+                    moveToNext()
+                } else {
+                    step = .openSettings
+                }
+            }
+        case (.prompt, .skipped), (.openSettings, .skipped), (.verify, .skipped):
+            if let kind = pending.first { skipped.append(kind) }
+            moveToNext()
+        default:
+            Log.debug("[permission] オンボーディング: \(step.rawValue) では \(event) を処理しません")
+        }
+    }
+
+    /// 現在のステップの処理（確認・ダイアログ表示・設定画面を開く）を実行して次へ進める
+    /// - 画面を持たない CLI などから使う。オートメーションのダイアログは応答まで待機するためメインスレッドでは呼ばない
+    mutating func advance() {
+        switch step {
+        case .check:
+            handle(.checked(PermissionChecker.currentStatus()))
+        case .prompt:
+            guard let kind = current else { return moveToNext() }
+            switch kind {
+            case .accessibility: _ = PermissionChecker.checkAccessibility(prompt: true)
+            case .screenRecording: _ = PermissionChecker.checkScreenRecording(prompt: true)
+            // System Events が起動していないとダイアログが出ないため、起動してから要求する
+            case .automation: _ = PermissionChecker.checkSystemEventsAutomation(prompt: true)
+            }
+            handle(.prompted)
+        case .openSettings:
+            guard let kind = current else { return moveToNext() }
+            PermissionChecker.openSettings(for: kind)
+            handle(.settingsOpened)
+        case .verify:
+            guard let kind = current else { return moveToNext() }
+            handle(.verified(granted: PermissionChecker.isGranted(kind)))
+        case .completed:
+            break
+        }
+    }
+
+    /// 最初からやり直す（スキップした権限も対象に戻す）
+    mutating func reset() {
+        self = PermissionOnboarding()
+    }
+
+    private mutating func moveToNext() {
+        if !pending.isEmpty { pending.removeFirst() }
+        attempts = 0
+        step = pending.isEmpty ? .completed : .prompt
+    }
+
+    // MARK: - 保存
+
+    /// 保存済みの進行状況（無い場合は最初から）
+    static func load() -> PermissionOnboarding {
+        return (try? FileHelper.loadJSON(PermissionOnboarding.self, from: FileHelper.onboardingStateURL())) ?? PermissionOnboarding()
+    }
+
+    func save() throws {
+        try FileHelper.saveJSON(self, to: FileHelper.onboardingStateURL())
+    }
+}
@@ -0,0 +1,67 @@
+import Foundation
+
+/// 長い復元の進捗を1件の通知で表示し、完了時に結果の通知へ置き換える
+/// - handler を restoreWindows などの progress に渡し、復元後に finish を呼ぶ
+/// - minimumWindows 件未満の短い復元では進捗を表示しない（完了時の結果のみ）
+/// - 進捗の更新は updateInterval 秒ごと。osascript での通知（バンドル外）は置き換えられないため結果のみ表示する
+final class RestoreProgressNotifier {
+    let layoutName: String
+    private let identifier = "restore-progress-\(UUID().uuidString)"
+    private let minimumWindows: Int
+    private let updateInterval: TimeInterval
+
+    private let lock = NSLock()
+    private var processed = 0
+    private var total = 0
+    private var restored = 0
+    private var lastPostedAt: Date?
+
+    init(layoutName: String, minimumWindows: Int = 10, updateInterval: TimeInterval = 1.0) {
+        self.layoutName = layoutName
+        self.minimumWindows = minimumWindows
+        self.updateInterval = updateInterval
+    }
+
+    /// 復元処理に渡す進捗ハンドラ（ワーカースレッドから呼ばれる）
+    var handler: RestoreProgressHandler {
+        return { [weak self] _, total, _, status in
+            self?.record(total: total, status: status)
+        }
+    }
+
+    /// 進捗の通知を結果に置き換える
+    /// - errorMessage: 復元に失敗した場合のエラー内容
+    func finish(errorMessage: String? = nil) {
+        lock.lock()
+        let (processed, total, restored) = (self.processed, self.total, self.restored)
+        lock.unlock()
+        if let errorMessage = errorMessage {
+            NotificationManager.post(title: "復元エラー", body: errorMessage, identifier: identifier)
+        } else if total > 0 {
+            NotificationManager.post(title: "復元完了",
+                                     body: "レイアウト「\(layoutName)」を復元しました（\(restored)/\(total) ウィンドウ\(processed < total ? "、途中で打ち切り" : "")）",
+                                     identifier: identifier)
+        } else {
+            NotificationManager.post(title: "復元完了", body: "レイアウト「\(layoutName)」が復元されました", identifier: identifier)
+        }
+    }
+
+    private func record(total: Int, status: RestoreStatus) {
+        lock.lock()
+        processed += 1
+        self.total = total
+        if status == .restored { restored += 1 }
+        let processed = self.processed
+        let now = Date()
+        let shouldPost = NotificationManager.usesNativeNotifications
+            && total >= minimumWindows
+            && processed < total
+            && lastPostedAt.map { now.timeIntervalSince($0) >= updateInterval } ?? true
+        if shouldPost { lastPostedAt = now }
+        lock.unlock()
+        guard shouldPost else { return }
+        NotificationManager.post(title: "レイアウトを復元中",
+                                 body: "「\(layoutName)」を復元しています: \(processed)/\(total) ウィンドウ",
+                                 identifier: identifier)
+    }
+}
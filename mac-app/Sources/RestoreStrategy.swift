@@ -0,0 +1,76 @@
+import Foundation
+import CoreGraphics
+import ApplicationServices
+
+/// アプリ固有のウィンドウ配置方法
+/// - 標準の AX 操作では正しく配置できないアプリ（Electron/Java/X11 系など）向けに bundle id ごとに登録する
+protocol RestoreStrategy {
+    /// ウィンドウを指定フレーム（CG グローバル座標）へ配置
+    /// 戻り値: 配置できた場合 true
+    func apply(frame: CGRect, to window: AXUIElement, info: WindowInfo, pid: pid_t) -> Bool
+}
+
+/// 既定の配置方法: AX で位置・サイズを設定
+/// - 失敗したウィンドウは復元処理の最後にプロセス単位で AppleScript によりまとめて配置される
+struct DefaultRestoreStrategy: RestoreStrategy {
+    func apply(frame: CGRect, to window: AXUIElement, info: WindowInfo, pid: pid_t) -> Bool {
+        return AccessibilityHelper.setFrame(window, frame)
+    }
+}
+
+/// 補間しながら段階的に配置（急な移動を避け、大きな一回の移動を拒否するアプリでも追従させる）
+struct AnimatedRestoreStrategy: RestoreStrategy {
+    /// 全体の所要時間（ミリ秒）
+    let durationMs: UInt64
+    // 1ステップの間隔（ミリ秒）
+    private static let stepIntervalMs: UInt64 = 16
+
+    func apply(frame: CGRect, to window: AXUIElement, info: WindowInfo, pid: pid_t) -> Bool {
+        guard let start = AccessibilityHelper.frame(of: window), durationMs > 0 else {
+            return AccessibilityHelper.setFrame(window, frame)
+        }
+        let steps = max(1, Int(durationMs / AnimatedRestoreStrategy.stepIntervalMs))
+        for step in 1..<steps {
+            // ease-out（終盤ほどゆっくり）
+            let t = 1 - pow(1 - Double(step) / Double(steps), 2)
+            let intermediate = CGRect(x: start.minX + (frame.minX - start.minX) * t,
+                                      y: start.minY + (frame.minY - start.minY) * t,
+                                      width: start.width + (frame.width - start.width) * t,
+                                      height: start.height + (frame.height - start.height) * t)
+            _ = AccessibilityHelper.setPosition(window, intermediate.origin)
+            _ = AccessibilityHelper.setSize(window, intermediate.size)
+            usleep(useconds_t(AnimatedRestoreStrategy.stepIntervalMs * 1000))
+        }
+        return AccessibilityHelper.setFrame(window, frame)
+    }
+}
+
+/// bundle id ごとの配置方法の登録先
+enum RestoreStrategyRegistry {
+    private static let lock = NSLock()
+    private static var strategies: [String: RestoreStrategy] = [:]
+    private static let defaultStrategy: RestoreStrategy = DefaultRestoreStrategy()
+
+    /// bundle id に配置方法を登録（既存の登録は置き換える）
+    static func register(_ strategy: RestoreStrategy, for bundleId: String) {
+        lock.lock()
+        defer { lock.unlock() }
+        strategies[bundleId] = strategy
+    }
+
+    static func unregister(bundleId: String) {
+        lock.lock()
+        defer { lock.unlock() }
+        strategies.removeValue(forKey: bundleId)
+    }
+
+    /// bundle id に対応する配置方法（未登録/bundle id 不明の場合は既定。設定で有効なら段階的に移動）
+    static func strategy(for bundleId: String?, config: Config) -> RestoreStrategy {
+        lock.lock()
+        defer { lock.unlock() }
+        if let registered = bundleId.flatMap({ strategies[$0] }) {
+            return registered
+        }
+        return config.animateWindowMoves ? AnimatedRestoreStrategy(durationMs: config.animationDurationMs) : defaultStrategy
+    }
+}
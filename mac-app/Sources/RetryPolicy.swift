@@ -0,0 +1,52 @@
+import Foundation
+
+/// 復元時のリトライ方針（Config の max_retry_attempts / retry_* から生成）
+/// - 待機時間は initialDelayMs から multiplier 倍ずつ伸ばし、maxDelayMs で頭打ちにする
+struct RetryPolicy {
+    let maxAttempts: Int
+    let initialDelayMs: UInt64
+    let multiplier: Double
+    let maxDelayMs: UInt64
+    /// この時刻を過ぎるリトライは行わない（復元全体の制限時間）
+    var deadline: Date? = nil
+
+    init(maxAttempts: Int, initialDelayMs: UInt64, multiplier: Double, maxDelayMs: UInt64) {
+        self.maxAttempts = max(0, maxAttempts)
+        self.initialDelayMs = initialDelayMs
+        self.multiplier = max(1.0, multiplier)
+        self.maxDelayMs = max(initialDelayMs, maxDelayMs)
+    }
+
+    init(config: Config) {
+        self.init(maxAttempts: Int(config.maxRetryAttempts),
+                  initialDelayMs: config.retryInitialDelayMs,
+                  multiplier: config.retryBackoffMultiplier,
+                  maxDelayMs: config.retryMaxDelayMs)
+    }
+
+    /// n 回目（1始まり）のリトライ前の待機時間（ミリ秒）
+    func delayMs(beforeRetry n: Int) -> UInt64 {
+        let delay = Double(initialDelayMs) * pow(multiplier, Double(max(0, n - 1)))
+        // 回数が多いと UInt64 の範囲を超えるため、変換前に上限と比べる
+        guard delay < Double(maxDelayMs) else { return maxDelayMs }
+        return min(UInt64(delay.rounded()), maxDelayMs)
+    }
+
+    /// body が nil 以外を返すまで、待機を挟んで最大 maxAttempts 回までリトライ
+    /// 戻り値: 最初に得られた値（すべて失敗した場合は nil）
+    func run<T>(_ label: String, _ body: () -> T?) -> T? {
+        if let value = body() { return value }
+        for attempt in stride(from: 1, through: maxAttempts, by: 1) {
+            let delay = delayMs(beforeRetry: attempt)
+            if let deadline = deadline, Date().addingTimeInterval(Double(delay) / 1000) >= deadline {
+                Log.warning("[restore] \(label): 制限時間のためリトライを打ち切りました")
+                return nil
+            }
+            Log.debug("[restore] \(label): \(delay)ms 後にリトライ (\(attempt)/\(maxAttempts))")
+            // ミリ秒→マイクロ秒の乗算で UInt64 があふれないよう Double で計算して上限に丸める
+            usleep(useconds_t(min(Double(delay) * 1000, Double(useconds_t.max))))
+            if let value = body() { return value }
+        }
+        return nil
+    }
+}
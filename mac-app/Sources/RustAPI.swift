@@ -15,7 +15,9 @@ struct RustAPI {
     private static let CODE_UNKNOWN: Int32 = 99
 
     static func initLibrary() -> RustResult<Void> {
-        // Swift単独化のため初期化は不要
+        // Swift単独化のため初期化は不要（ログの設定と、同期フォルダの競合の解決のみ起動時に行う）
+        Log.configure(with: Config.load())
+        WindowManager.shared.resolveSyncConflicts()
         return .success(())
     }
 
@@ -27,6 +29,8 @@ struct RustAPI {
         do {
             try WindowManager.shared.saveWindows(name: name)
             return .success(())
+        } catch FileHelper.FileHelperError.reservedLayoutName {
+            return .failure(code: CODE_FILEIO, message: "auto_ で始まる名前は自動スナップショット用のため使えません: \(name)")
         } catch {
             return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの保存に失敗しました: \(error.localizedDescription)"))
         }
@@ -38,11 +42,240 @@ struct RustAPI {
             return .success(())
         } catch {
             // 権限不足の場合とそれ以外を大まかに分類
-            let code: Int32 = WindowManager.shared.hasAccessibilityPermission() ? CODE_UNKNOWN : CODE_PERMISSION
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
             return .failure(code: code, message: errorMessage(fallback: "レイアウトの復元に失敗しました: \(error.localizedDescription)"))
         }
     }
 
+    /// 進捗を通知しながらレイアウトを復元
+    static func restoreLayoutWithProgress(name: String, progress: @escaping RestoreProgressHandler) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreWindows(name: name, progress: progress)
+            return .success(())
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
+            return .failure(code: code, message: errorMessage(fallback: "レイアウトの復元に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 設定の default_layout を復元
+    static func restoreDefault() -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreDefault()
+            return .success(())
+        } catch FileHelper.FileHelperError.layoutNotFound {
+            return .failure(code: CODE_FILEIO, message: "既定のレイアウトが設定されていないか、見つかりません")
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
+            return .failure(code: code, message: errorMessage(fallback: "レイアウトの復元に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 既定のレイアウトを設定（nil で解除）
+    static func setDefaultLayout(name: String?) -> RustResult<Void> {
+        return updateConfig { $0.defaultLayout = name }
+    }
+
+    /// 指定アプリ（bundle id またはアプリ名）のウィンドウのみ復元
+    static func restoreLayoutFiltered(name: String, apps: [String]) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreWindowsFiltered(name: name, apps: apps)
+            return .success(())
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
+            return .failure(code: code, message: errorMessage(fallback: "レイアウトの復元に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 指定ディスプレイのウィンドウのみ保存（既存レイアウトの他のディスプレイのウィンドウは残す）
+    static func saveLayoutForDisplay(name: String, displayUUID: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.saveWindowsForDisplay(name: name, displayUUID: displayUUID)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの保存に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 指定ディスプレイに保存されていたウィンドウのみ復元
+    static func restoreLayoutForDisplay(name: String, displayUUID: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreWindowsForDisplay(name: name, displayUUID: displayUUID)
+            return .success(())
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
+            return .failure(code: code, message: errorMessage(fallback: "レイアウトの復元に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 復元完了後にアクティブにするアプリを設定（nil で解除）
+    static func setLayoutFocusApp(name: String, bundleId: String?) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.setFocusApp(name: name, bundleId: bundleId)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの更新に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 直前の復元を取り消す
+    static func undoLastRestore() -> RustResult<Void> {
+        do {
+            try WindowManager.shared.undoLastRestore()
+            return .success(())
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_FILEIO)
+            return .failure(code: code, message: errorMessage(fallback: "復元の取り消しに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトをバックアップ世代（1 が最新）の内容に戻す
+    static func restoreLayoutBackup(name: String, generation: Int) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreLayoutBackup(name: name, generation: generation)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "バックアップからの復旧に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトの説明・タグ・ショートカットを取得
+    static func layoutMetadata(name: String) -> RustResult<LayoutMetadata> {
+        do {
+            return .success(try WindowManager.shared.layoutMetadata(name: name))
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの読み込みに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトの説明・タグ・ショートカットを更新
+    static func updateLayoutMetadata(name: String, metadata: LayoutMetadata) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.updateLayoutMetadata(name: name, metadata)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの更新に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 保存時と現在のディスプレイ構成の一致度（構成が記録されていない場合は nil）
+    static func layoutDisplayMatch(name: String) -> RustResult<DisplayMatch?> {
+        do {
+            return .success(try WindowManager.shared.layoutDisplayMatch(name: name))
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの読み込みに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウト全体（ウィンドウ一覧とメタデータ）を取得
+    static func getLayout(name: String) -> RustResult<Layout> {
+        do {
+            return .success(try WindowManager.shared.loadLayout(name: name))
+        } catch is DecodingError {
+            return .failure(code: CODE_JSON, message: "レイアウトの形式が不正です: \(name)")
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの読み込みに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトの概要（アプリごとのウィンドウ数、日時、メタデータ）を取得
+    static func getLayoutSummary(name: String) -> RustResult<LayoutSummary> {
+        do {
+            return .success(try WindowManager.shared.layoutSummary(name: name))
+        } catch is DecodingError {
+            return .failure(code: CODE_JSON, message: "レイアウトの形式が不正です: \(name)")
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの読み込みに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトの配置のプレビュー（比率で表した矩形の一覧）を取得
+    static func getLayoutPreview(name: String) -> RustResult<LayoutPreview> {
+        do {
+            return .success(try WindowManager.shared.layoutPreview(name: name))
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの読み込みに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// レイアウトの配置のプレビューを SVG で取得
+    static func getLayoutPreviewSVG(name: String, width: Double = 320) -> RustResult<String> {
+        switch getLayoutPreview(name: name) {
+        case .success(let preview): return .success(preview.svg(width: CGFloat(width)))
+        case .failure(let code, let message): return .failure(code: code, message: message)
+        }
+    }
+
+    /// すべてのレイアウトの概要を取得
+    static func getLayoutSummaries() -> RustResult<[LayoutSummary]> {
+        return .success(WindowManager.shared.layoutSummaries())
+    }
+
+    /// 現在のウィンドウ配置を日時付きの名前で自動保存し、保存した名前を返す
+    static func saveAutoSnapshot() -> RustResult<String> {
+        do {
+            return .success(try WindowManager.shared.saveAutoSnapshot())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "スナップショットの保存に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 一定間隔（省略時は設定の scan_interval_ms）での自動スナップショットを開始
+    static func startAutoSave(intervalMs: UInt64? = nil) -> RustResult<Void> {
+        if let intervalMs = intervalMs, intervalMs < 500 {
+            return .failure(code: CODE_UNKNOWN, message: "自動保存の間隔は 500 ms 以上にしてください")
+        }
+        if let intervalMs = intervalMs, intervalMs > AutoSaveScheduler.maxIntervalMs {
+            return .failure(code: CODE_UNKNOWN, message: "自動保存の間隔は 24 時間（\(AutoSaveScheduler.maxIntervalMs) ms）以下にしてください")
+        }
+        WindowManager.shared.startAutoSave(intervalMs: intervalMs)
+        return .success(())
+    }
+
+    static func stopAutoSave() -> RustResult<Void> {
+        WindowManager.shared.stopAutoSave()
+        return .success(())
+    }
+
+    static func isAutoSaveRunning() -> Bool {
+        return WindowManager.shared.isAutoSaveRunning
+    }
+
+    /// レイアウトをディスプレイに対する比率のテンプレートとして保存
+    static func saveLayoutAsTemplate(layoutName: String, templateName: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.saveTemplate(name: templateName, fromLayout: layoutName)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "テンプレートの保存に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// テンプレートを現在のディスプレイ構成に当てはめて復元
+    static func restoreTemplate(name: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.restoreTemplate(name: name)
+            return .success(())
+        } catch {
+            let code = errorCode(for: error, fallback: CODE_UNKNOWN)
+            return .failure(code: code, message: errorMessage(fallback: "テンプレートの復元に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    static func listTemplates() -> RustResult<[String]> {
+        return .success(WindowManager.shared.listTemplates())
+    }
+
+    /// レイアウトを固定（保存数の上限を超えても自動削除しない）
+    static func setLayoutPinned(name: String, pinned: Bool) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.setLayoutPinned(name: name, pinned: pinned)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの更新に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
     static func deleteLayout(name: String) -> RustResult<Void> {
         do {
             try WindowManager.shared.deleteLayout(name: name)
@@ -52,15 +285,381 @@ struct RustAPI {
         }
     }
 
+    static func renameLayout(from oldName: String, to newName: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.renameLayout(from: oldName, to: newName)
+            return .success(())
+        } catch FileHelper.FileHelperError.layoutAlreadyExists {
+            return .failure(code: CODE_FILEIO, message: "同じ名前のレイアウトが既に存在します: \(newName)")
+        } catch FileHelper.FileHelperError.invalidLayoutName {
+            return .failure(code: CODE_FILEIO, message: "レイアウト名が不正です: \(newName)")
+        } catch FileHelper.FileHelperError.reservedLayoutName {
+            return .failure(code: CODE_FILEIO, message: "auto_ で始まる名前は自動スナップショット用のため使えません: \(newName)")
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウト名の変更に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 2つのレイアウトを合成して保存（保存先を省略した場合は base を上書き）
+    static func mergeLayouts(base: String, overlay: String, strategy: LayoutMergeStrategy, into target: String? = nil) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.mergeLayouts(base: base, overlay: overlay, strategy: strategy, into: target)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの合成に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 指定レイアウトを1つのアーカイブファイルへ書き出す
+    static func exportLayouts(names: [String], path: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.exportLayouts(names: names, to: URL(fileURLWithPath: path))
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトのエクスポートに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// アーカイブファイルからレイアウトを取り込み、保存した名前を返す
+    static func importLayouts(path: String, onConflict policy: ImportConflictPolicy = .rename) -> RustResult<[String]> {
+        do {
+            return .success(try WindowManager.shared.importLayouts(from: URL(fileURLWithPath: path), onConflict: policy))
+        } catch is DecodingError {
+            return .failure(code: CODE_JSON, message: "アーカイブの形式が不正です: \(path)")
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトのインポートに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    static func refreshLayout(name: String, bundleId: String) -> RustResult<Void> {
+        do {
+            try WindowManager.shared.refreshWindows(name: name, bundleId: bundleId)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "レイアウトの更新に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 同期フォルダで生じたレイアウトの競合を解決し、対象のレイアウト名を返す
+    static func resolveLayoutSyncConflicts() -> RustResult<[String]> {
+        return .success(WindowManager.shared.resolveSyncConflicts())
+    }
+
+    static func scanWindows(bundleId: String) -> RustResult<[WindowInfo]> {
+        return .success(WindowManager.shared.fetchWindows(forBundleId: bundleId))
+    }
+
+    static func currentWindows() -> RustResult<[WindowInfo]> {
+        return .success(WindowManager.shared.cachedWindows())
+    }
+
     static func listLayouts() -> RustResult<[String]> {
         let layouts = WindowManager.shared.listLayouts()
         return .success(layouts)
     }
 
+    /// 名前・説明・タグ・含まれるアプリ名/bundle id でレイアウトを検索
+    static func findLayouts(query: String) -> RustResult<[String]> {
+        return .success(WindowManager.shared.findLayouts(matching: query))
+    }
+
+    /// 保存されている設定を JSON 文字列で取得（config.json と同じキー。環境変数の上書きは含まない）
+    /// - setConfigJSON で書き戻しても環境変数の値がファイルに保存されないように
+    static func getConfigJSON() -> RustResult<String> {
+        return configJSON(Config.load(applyEnvironment: false))
+    }
+
+    /// 実際に使われる設定（WINDOW_RESTORE_* 環境変数の上書きを適用済み）を JSON 文字列で取得
+    static func getEffectiveConfigJSON() -> RustResult<String> {
+        return configJSON(Config.load())
+    }
+
+    private static func configJSON(_ config: Config) -> RustResult<String> {
+        do {
+            let data = try SerializationFormat.json.encode(config)
+            return .success(String(decoding: data, as: UTF8.self))
+        } catch {
+            return .failure(code: CODE_JSON, message: errorMessage(fallback: "設定の変換に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// JSON 文字列の設定を検証して保存（欠落した項目は既定値。検証エラーがある場合は保存しない）
+    /// - 古い形式の項目は移行し、現在の設定バージョンとして保存する（version の無い JSON が次回読み込み時に再移行されないように）
+    static func setConfigJSON(_ json: String) -> RustResult<Void> {
+        var config: Config
+        do {
+            config = try JSONDecoder().decode(Config.self, from: Data(json.utf8))
+        } catch {
+            return .failure(code: CODE_JSON, message: "設定の形式が不正です: \(error.localizedDescription)")
+        }
+        config.migrate()
+        config.version = Config.currentVersion
+        return updateConfig { $0 = config }
+    }
+
+    /// 復元待機時間を設定（bundle id を指定した場合はそのアプリのみ）
+    static func setRestoreDelay(ms: UInt64, bundleId: String? = nil) -> RustResult<Void> {
+        return configResult { try Config.setRestoreDelay(ms, for: bundleId) }
+    }
+
+    /// 除外アプリ（bundle id・アプリ名・パターン）を追加
+    static func addExcludedApp(_ pattern: String) -> RustResult<Void> {
+        return configResult { try Config.addExcludedApp(pattern) }
+    }
+
+    /// 除外アプリを削除
+    static func removeExcludedApp(_ pattern: String) -> RustResult<Void> {
+        return configResult { try Config.removeExcludedApp(pattern) }
+    }
+
+    /// 起動時の自動復元を切り替え
+    static func setAutoRestore(_ enabled: Bool) -> RustResult<Void> {
+        return configResult { try Config.setAutoRestore(enabled) }
+    }
+
+    /// 設定を変更して保存（検証エラーがある場合は保存しない）
+    static func updateConfig(_ body: (inout Config) -> Void) -> RustResult<Void> {
+        return configResult { try Config.update(body) }
+    }
+
+    private static func configResult(_ body: () throws -> Void) -> RustResult<Void> {
+        do {
+            try body()
+            return .success(())
+        } catch ConfigUpdateError.invalid(let errors) {
+            return .failure(code: CODE_JSON, message: errors.map(\.message).joined(separator: "\n"))
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "設定の保存に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 設定ファイルの検証エラー（問題が無ければ空）
+    static func validateConfig() -> RustResult<[ConfigValidationError]> {
+        return .success(Config.load(repair: false).validate())
+    }
+
+    /// 説明コメント付きの config.toml を作成し、そのパスを返す（以降は config.json より優先される）
+    static func createConfigTOML() -> RustResult<String> {
+        do {
+            return .success(try Config.writeTOMLTemplate().path)
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "config.toml の作成に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 設定プロファイルの一覧
+    static func listConfigProfiles() -> RustResult<[String]> {
+        return .success(Config.listProfiles())
+    }
+
+    /// 使用する設定プロファイルを切り替え（nil で既定の設定に戻す）
+    static func setActiveConfigProfile(name: String?) -> RustResult<Void> {
+        do {
+            try Config.setActiveProfile(name)
+            return .success(())
+        } catch FileHelper.FileHelperError.profileNotFound {
+            return .failure(code: CODE_FILEIO, message: "プロファイルが見つかりません: \(name ?? "")")
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "プロファイルの切り替えに失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 現在の設定をプロファイルとして保存
+    static func saveConfigProfile(name: String) -> RustResult<Void> {
+        do {
+            try Config.load().save(asProfile: name)
+            return .success(())
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "プロファイルの保存に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 現在のディスプレイ配置の指紋
+    static func displayArrangementFingerprint() -> RustResult<String> {
+        return .success(DisplayManager.arrangementFingerprint())
+    }
+
+    /// 現在と同じディスプレイ配置で保存されたレイアウト（最近使った順）
+    static func layoutsForCurrentDisplays() -> RustResult<[String]> {
+        return .success(WindowManager.shared.layoutsMatchingCurrentDisplays())
+    }
+
+    /// ディスプレイの別名を設定（nil または空文字で解除）
+    static func setDisplayAlias(uuid: String, name: String?) -> RustResult<Void> {
+        return updateConfig { config in
+            let name = name?.trimmingCharacters(in: .whitespacesAndNewlines) ?? ""
+            config.displayAliases[uuid] = name.isEmpty ? nil : name
+        }
+    }
+
+    /// ディスプレイの位置関係（左から/上から何番目か、最も左か など）
+    static func displayPlacement(uuid: String) -> RustResult<DisplayPlacement> {
+        guard let placement = DisplayManager.placement(of: uuid) else {
+            return .failure(code: CODE_UNKNOWN, message: "ディスプレイが接続されていません: \(uuid)")
+        }
+        return .success(placement)
+    }
+
+    /// 指定ディスプレイより左にあるディスプレイ（近い順）
+    static func displaysLeftOf(uuid: String) -> RustResult<[DisplayInfo]> {
+        return .success(DisplayManager.displays(leftOf: uuid))
+    }
+
+    /// 指定ディスプレイより右にあるディスプレイ（近い順）
+    static func displaysRightOf(uuid: String) -> RustResult<[DisplayInfo]> {
+        return .success(DisplayManager.displays(rightOf: uuid))
+    }
+
+    /// 現在接続されているディスプレイの一覧
+    static func listDisplays() -> RustResult<[DisplayInfo]> {
+        return .success(DisplayManager.currentDisplays())
+    }
+
+    /// 現在接続されているディスプレイの一覧を JSON 文字列で取得（モニター選択やディスプレイごとのプレビュー表示用）
+    /// - 各要素: uuid, name, alias, frame{x,y,width,height}, visible_frame, scale, main, virtual（座標は CG グローバル座標）
+    /// - Swift の String で返すため、呼び出し側での解放は不要
+    static func getDisplayListJSON() -> RustResult<String> {
+        let config = Config.load()
+        func rect(_ r: CGRect) -> [String: Double] {
+            return ["x": Double(r.minX), "y": Double(r.minY), "width": Double(r.width), "height": Double(r.height)]
+        }
+        let list: [[String: Any]] = DisplayManager.currentDisplays().map { d in
+            var entry: [String: Any] = [
+                "uuid": d.uuid,
+                "name": d.name ?? d.uuid,
+                "frame": rect(d.bounds),
+                "scale": d.scaleFactor ?? 1.0,
+                "main": d.isMain,
+                "virtual": d.isVirtual ?? false,
+            ]
+            if let alias = config.displayAlias(for: d.uuid) { entry["alias"] = alias }
+            if let visible = d.visibleFrame { entry["visible_frame"] = rect(visible) }
+            return entry
+        }
+        do {
+            let data = try JSONSerialization.data(withJSONObject: list, options: [.prettyPrinted, .sortedKeys])
+            return .success(String(decoding: data, as: UTF8.self))
+        } catch {
+            return .failure(code: CODE_JSON, message: errorMessage(fallback: "ディスプレイ一覧の変換に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
     static func hasAccessibilityPermission() -> Bool {
         return WindowManager.shared.hasAccessibilityPermission()
     }
 
+    static func hasScreenRecordingPermission() -> Bool {
+        return PermissionChecker.checkScreenRecording()
+    }
+
+    /// 画面収録権限を要求（未許可ならシステムの許可ダイアログを表示）
+    /// 戻り値: 許可されている場合 true（新たに許可された場合はアプリの再起動後に有効）
+    @discardableResult
+    static func requestScreenRecordingPermission() -> Bool {
+        return PermissionChecker.checkScreenRecording(prompt: true)
+    }
+
+    /// オートメーション権限の状態（bundleId 省略時は AppleScript による配置で使う System Events）
+    static func automationPermissionStatus(bundleId: String = PermissionChecker.systemEventsBundleId) -> AutomationStatus {
+        return PermissionChecker.checkAutomation(bundleId: bundleId)
+    }
+
+    /// 現在使われる配置方法で必要な権限がすべて許可されているか確認
+    /// - 不足している場合は CODE_PERMISSION（メッセージに不足している権限を含む）
+    static func checkAllPermissions() -> RustResult<Void> {
+        let missing = PermissionChecker.currentStatus().missing
+        guard missing.isEmpty else {
+            return .failure(code: CODE_PERMISSION, message: PermissionDeniedError(missing: missing).localizedDescription)
+        }
+        return .success(())
+    }
+
+    /// 現在使われる配置方法（"accessibility", "applescript"）
+    static func activeRestoreBackends() -> [RestoreBackend] {
+        return PermissionChecker.activeBackends()
+    }
+
+    /// 権限ごとの状態を JSON 文字列で取得（accessibility, screen_recording, automation, backends, required）
+    static func getPermissionStatusJSON() -> RustResult<String> {
+        do {
+            let data = try SerializationFormat.json.encode(PermissionChecker.currentStatus())
+            return .success(String(decoding: data, as: UTF8.self))
+        } catch {
+            return .failure(code: CODE_JSON, message: errorMessage(fallback: "権限の状態の変換に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// システム設定で指定の権限のパネルを開く
+    @discardableResult
+    static func openPermissionSettings(_ kind: PermissionKind) -> Bool {
+        return PermissionChecker.openSettings(for: kind)
+    }
+
+    /// 設定画面を開いた後、指定の権限が実際に許可されたかを確認
+    static func verifyPermission(_ kind: PermissionKind) -> Bool {
+        return PermissionChecker.isGranted(kind)
+    }
+
+    /// 権限設定（オンボーディング）の進行状況を JSON 文字列で取得
+    static func getOnboardingStateJSON() -> RustResult<String> {
+        return onboardingResult(PermissionOnboarding.load())
+    }
+
+    /// オンボーディングの現在のステップを実行して次へ進め、進行状況を保存
+    /// - skip: true の場合は現在の権限の設定を見送る
+    static func advanceOnboarding(skip: Bool = false) -> RustResult<String> {
+        var onboarding = PermissionOnboarding.load()
+        if skip {
+            onboarding.handle(.skipped)
+        } else {
+            onboarding.advance()
+        }
+        do {
+            try onboarding.save()
+        } catch {
+            return .failure(code: CODE_FILEIO, message: errorMessage(fallback: "オンボーディングの状態の保存に失敗しました: \(error.localizedDescription)"))
+        }
+        return onboardingResult(onboarding)
+    }
+
+    private static func onboardingResult(_ onboarding: PermissionOnboarding) -> RustResult<String> {
+        do {
+            let data = try SerializationFormat.json.encode(onboarding)
+            return .success(String(decoding: data, as: UTF8.self))
+        } catch {
+            return .failure(code: CODE_JSON, message: errorMessage(fallback: "オンボーディングの状態の変換に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    /// 権限の確認結果と要求の記録を JSON 文字列で取得（古い順。limit 指定時は新しいものから limit 件）
+    static func getPermissionAuditLogJSON(limit: Int? = nil) -> RustResult<String> {
+        let encoder = JSONEncoder()
+        encoder.dateEncodingStrategy = .iso8601
+        encoder.outputFormatting = [.prettyPrinted, .sortedKeys]
+        do {
+            let data = try encoder.encode(PermissionAuditLog.entries(limit: limit))
+            return .success(String(decoding: data, as: UTF8.self))
+        } catch {
+            return .failure(code: CODE_JSON, message: errorMessage(fallback: "権限の記録の変換に失敗しました: \(error.localizedDescription)"))
+        }
+    }
+
+    static func clearPermissionAuditLog() {
+        PermissionAuditLog.clear()
+    }
+
+    /// アクセシビリティ権限を要求（未許可ならシステムの許可ダイアログを表示）
+    /// 戻り値: 呼び出し時点で許可されていた場合 true
+    @discardableResult
+    static func requestAccessibilityPermission() -> Bool {
+        return PermissionChecker.checkAccessibility(prompt: true)
+    }
+
+    // 権限不足のエラーは CODE_PERMISSION、それ以外は fallback に分類
+    private static func errorCode(for error: Error, fallback: Int32) -> Int32 {
+        return error is PermissionDeniedError ? CODE_PERMISSION : fallback
+    }
+
     private static func errorMessage(fallback: String) -> String { fallback }
 }
 
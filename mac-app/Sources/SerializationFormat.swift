@@ -0,0 +1,58 @@
+import Foundation
+import Yams
+import TOMLKit
+
+/// 設定/レイアウトファイルの形式（拡張子で判別）
+/// - 手で編集しやすいよう YAML / TOML も読み書きできる
+/// - 既定は JSON。アプリ内部や API でのやり取りは常に JSON
+enum SerializationFormat: String, Codable, CaseIterable {
+    case json
+    case yaml
+    case toml
+
+    /// 保存時に使う拡張子
+    var fileExtension: String {
+        return rawValue
+    }
+
+    /// 拡張子から形式を判別（未対応の拡張子は nil）
+    init?(pathExtension: String) {
+        switch pathExtension.lowercased() {
+        case "json": self = .json
+        case "yaml", "yml": self = .yaml
+        case "toml": self = .toml
+        default: return nil
+        }
+    }
+
+    init?(url: URL) {
+        self.init(pathExtension: url.pathExtension)
+    }
+
+    /// 読み込み対象とする拡張子
+    static let supportedExtensions: Set<String> = ["json", "yaml", "yml", "toml"]
+
+    func encode<T: Encodable>(_ value: T) throws -> Data {
+        switch self {
+        case .json:
+            let encoder = JSONEncoder()
+            encoder.outputFormatting = [.prettyPrinted, .sortedKeys]
+            return try encoder.encode(value)
+        case .yaml:
+            return Data(try YAMLEncoder().encode(value).utf8)
+        case .toml:
+            return Data(try TOMLEncoder().encode(value).utf8)
+        }
+    }
+
+    func decode<T: Decodable>(_ type: T.Type, from data: Data) throws -> T {
+        switch self {
+        case .json:
+            return try JSONDecoder().decode(T.self, from: data)
+        case .yaml:
+            return try YAMLDecoder().decode(T.self, from: data)
+        case .toml:
+            return try TOMLDecoder().decode(T.self, from: String(decoding: data, as: UTF8.self))
+        }
+    }
+}
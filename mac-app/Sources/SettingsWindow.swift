@@ -111,7 +111,7 @@ class SettingsWindow: NSWindow {
         contentView.addSubview(restoreDelayLabel)
         
         // 除外アプリケーション設定
-        let excludedAppsLabel = NSTextField(labelWithString: "除外するアプリケーション（1行に1つ）:")
+        let excludedAppsLabel = NSTextField(labelWithString: "除外するアプリケーション（bundle id・アプリ名・com.foo.* や name~\"正規表現\"、1行に1つ）:")
         excludedAppsLabel.frame = NSRect(x: 20, y: 200, width: 460, height: 24)
         contentView.addSubview(excludedAppsLabel)
         
@@ -124,7 +124,7 @@ class SettingsWindow: NSWindow {
         excludedAppsTextView.isEditable = true
         excludedAppsTextView.isRichText = false
         excludedAppsTextView.font = NSFont.systemFont(ofSize: 12)
-        excludedAppsTextView.string = "Finder\nDock"
+        excludedAppsTextView.string = Config().excludeApps.joined(separator: "\n")
         
         scrollView.documentView = excludedAppsTextView
         contentView.addSubview(scrollView)
@@ -150,54 +150,46 @@ class SettingsWindow: NSWindow {
     // MARK: - 設定の読み込みと保存
     
     /// 設定を読み込み
-    /// UserDefaultsから設定を読み込んでUIに反映
+    /// config.json から設定を読み込んでUIに反映
     private func loadSettings() {
-        let defaults = UserDefaults.standard
+        let config = Config.load()
         
         // 自動復元設定
-        let autoRestore = defaults.bool(forKey: "autoRestore")
-        autoRestoreCheckbox.state = autoRestore ? .on : .off
+        autoRestoreCheckbox.state = config.autoRestore ? .on : .off
         
         // ディスプレイ変化検知設定
-        let detectDisplayChanges = defaults.bool(forKey: "detectDisplayChanges")
-        detectDisplayChangesCheckbox.state = detectDisplayChanges ? .on : .off
+        detectDisplayChangesCheckbox.state = config.displayChangeDetection ? .on : .off
         
         // 復元遅延設定
-        let restoreDelay = defaults.integer(forKey: "restoreDelay")
-        if restoreDelay > 0 {
-            restoreDelaySlider.doubleValue = Double(restoreDelay)
-            restoreDelayLabel.stringValue = "\(restoreDelay) ms"
-        }
+        restoreDelaySlider.doubleValue = Double(config.restoreDelayMs)
+        restoreDelayLabel.stringValue = "\(config.restoreDelayMs) ms"
         
         // 除外アプリケーション設定
-        if let excludedApps = defaults.array(forKey: "excludedApps") as? [String] {
-            excludedAppsTextView.string = excludedApps.joined(separator: "\n")
-        }
+        excludedAppsTextView.string = config.excludeApps.joined(separator: "\n")
     }
     
     /// 設定を保存
-    /// UIの状態をUserDefaultsに保存
+    /// UIの状態を config.json に保存
     @objc private func saveSettings() {
-        let defaults = UserDefaults.standard
-        
-        // 自動復元設定
-        defaults.set(autoRestoreCheckbox.state == .on, forKey: "autoRestore")
-        
-        // ディスプレイ変化検知設定
-        defaults.set(detectDisplayChangesCheckbox.state == .on, forKey: "detectDisplayChanges")
-        
-        // 復元遅延設定
-        defaults.set(Int(restoreDelaySlider.doubleValue), forKey: "restoreDelay")
-        
-        // 除外アプリケーション設定
-        let excludedAppsText = excludedAppsTextView.string
-        let excludedApps = excludedAppsText.components(separatedBy: "\n")
+        let autoRestore = autoRestoreCheckbox.state == .on
+        let displayChangeDetection = detectDisplayChangesCheckbox.state == .on
+        let restoreDelayMs = UInt64(restoreDelaySlider.doubleValue)
+        let excludeApps = excludedAppsTextView.string.components(separatedBy: "\n")
             .map { $0.trimmingCharacters(in: .whitespacesAndNewlines) }
             .filter { !$0.isEmpty }
-        defaults.set(excludedApps, forKey: "excludedApps")
         
-        // 保存を実行
-        defaults.synchronize()
+        // 保存済みの設定を読み直して画面の項目だけを反映（他で変更された項目は上書きしない）
+        do {
+            try Config.update { config in
+                config.autoRestore = autoRestore
+                config.displayChangeDetection = displayChangeDetection
+                config.restoreDelayMs = restoreDelayMs
+                config.excludeApps = excludeApps
+            }
+        } catch {
+            showSaveErrorAlert(message: error.localizedDescription)
+            return
+        }
         
         // デリゲートに通知
         settingsDelegate?.settingsDidChange()
@@ -242,6 +234,17 @@ class SettingsWindow: NSWindow {
         alert.runModal()
     }
     
+    /// 保存失敗アラートを表示
+    /// 引数: message - エラーメッセージ
+    private func showSaveErrorAlert(message: String) {
+        let alert = NSAlert()
+        alert.messageText = "設定の保存に失敗しました"
+        alert.informativeText = message
+        alert.alertStyle = .critical
+        alert.addButton(withTitle: "OK")
+        alert.runModal()
+    }
+    
     // MARK: - 公開メソッド
     
     /// 設定ウィンドウを表示
@@ -0,0 +1,15 @@
+import Foundation
+
+/// スキャン結果一式（ウィンドウ配列＋取得時刻＋ディスプレイ構成の指紋）
+/// - 指紋により、保存時と同じモニター構成かどうかを後から判定できる
+struct Snapshot: Codable {
+    let windows: [WindowInfo]
+    let capturedAt: Date
+    /// DisplayManager.arrangementFingerprint()
+    let displayFingerprint: String
+
+    /// 現在のディスプレイ構成と同じ構成で取得されたか
+    var matchesCurrentDisplays: Bool {
+        return displayFingerprint == DisplayManager.arrangementFingerprint()
+    }
+}
@@ -0,0 +1,40 @@
+import Foundation
+import CoreGraphics
+
+/// Mission Control の Space 情報取得ヘルパー
+/// - 公開APIが存在しないため SkyLight の非公開関数を実行時に解決する
+/// - 解決できない環境では nil を返し、Space 情報なしで動作を継続する
+enum SpaceHelper {
+
+    private typealias MainConnectionIDFn = @convention(c) () -> Int32
+    private typealias CopySpacesForWindowsFn = @convention(c) (Int32, Int32, CFArray) -> Unmanaged<CFArray>?
+
+    // kCGSAllSpacesMask 相当
+    private static let allSpacesMask: Int32 = 0x7
+
+    private static let mainConnectionID: MainConnectionIDFn? = resolve("CGSMainConnectionID")
+    private static let copySpacesForWindows: CopySpacesForWindowsFn? = resolve("CGSCopySpacesForWindows")
+
+    /// Space 情報を取得できる環境か
+    static var isAvailable: Bool {
+        return mainConnectionID != nil && copySpacesForWindows != nil
+    }
+
+    /// ウィンドウが属する Space ID（複数 Space に属する場合は先頭。取得不可なら nil）
+    static func spaceID(forWindowNumber windowNumber: Int) -> Int? {
+        guard let mainConnectionID = mainConnectionID,
+              let copySpacesForWindows = copySpacesForWindows else { return nil }
+        let windows = [NSNumber(value: windowNumber)] as CFArray
+        guard let spaces = copySpacesForWindows(mainConnectionID(), allSpacesMask, windows)?.takeRetainedValue() as? [NSNumber] else {
+            return nil
+        }
+        return spaces.first?.intValue
+    }
+
+    private static func resolve<T>(_ symbol: String) -> T? {
+        // RTLD_DEFAULT
+        let handle = UnsafeMutableRawPointer(bitPattern: -2)
+        guard let pointer = dlsym(handle, symbol) else { return nil }
+        return unsafeBitCast(pointer, to: T.self)
+    }
+}
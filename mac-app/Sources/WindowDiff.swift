@@ -0,0 +1,56 @@
+import Foundation
+import CoreGraphics
+
+/// 2つのスナップショット間のウィンドウ差分
+/// - 自動保存などで全体を書き直さずに変化のみを扱うために使用
+struct WindowDiff {
+    /// 移動/リサイズされたウィンドウ（前回と今回のペア）
+    struct Moved {
+        let previous: WindowInfo
+        let current: WindowInfo
+    }
+
+    let added: [WindowInfo]
+    let removed: [WindowInfo]
+    let moved: [Moved]
+
+    var isEmpty: Bool {
+        return added.isEmpty && removed.isEmpty && moved.isEmpty
+    }
+
+    // フレーム変化とみなす最小差分（px）
+    static let frameTolerance: CGFloat = 1.0
+
+    /// 差分を計算
+    /// - 同一ウィンドウの判定は CGWindowNumber（PID込み）を優先し、無い場合はアプリ＋タイトル＋AXIdentifier
+    static func compute(previous: [WindowInfo], current: [WindowInfo]) -> WindowDiff {
+        var remaining: [String: [WindowInfo]] = [:]
+        for w in previous {
+            remaining[identity(of: w), default: []].append(w)
+        }
+
+        var added: [WindowInfo] = []
+        var moved: [Moved] = []
+        for w in current {
+            let key = identity(of: w)
+            guard var bucket = remaining[key], !bucket.isEmpty else {
+                added.append(w)
+                continue
+            }
+            let old = bucket.removeFirst()
+            remaining[key] = bucket
+            if !old.bounds.isApproximatelyEqual(to: w.bounds, tolerance: frameTolerance) {
+                moved.append(Moved(previous: old, current: w))
+            }
+        }
+        let removed = remaining.values.flatMap { $0 }
+        return WindowDiff(added: added, removed: removed, moved: moved)
+    }
+
+    private static func identity(of w: WindowInfo) -> String {
+        if let number = w.windowNumber {
+            return "wid:\(w.pid):\(number)"
+        }
+        return "key:\(w.bundleId ?? w.ownerName):\(w.windowName ?? ""):\(w.axIdentifier ?? "")"
+    }
+}
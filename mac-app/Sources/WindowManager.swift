@@ -7,13 +7,134 @@ import ApplicationServices
 struct WindowInfo: Codable {
     let ownerName: String
     let pid: Int
-    let windowName: String?
-    let bounds: CGRect
-    let displayUUID: String?
+    var windowName: String?
+    var bounds: CGRect
+    var displayUUID: String?
     let spaceNumber: Int?
-    let layoutLabel: String?
+    var layoutLabel: String?
+    /// 所有アプリの bundle id（復元時のアプリ特定/起動に使用。旧データは nil）
+    var bundleId: String? = nil
+    /// CGWindowNumber（同一プロセスが生存している間のみ有効）
+    var windowNumber: Int? = nil
+    /// AXIdentifier（アプリが設定している場合のみ。再起動後も安定）
+    var axIdentifier: String? = nil
+    /// AXRole / AXSubrole（標準ウィンドウ/シート/ダイアログ等の区別）
+    var axRole: String? = nil
+    var axSubrole: String? = nil
+    /// 保存時の重なり順（0 = 最前面。最小化/非表示など画面外のウィンドウは nil）
+    var zIndex: Int? = nil
+    /// フルスクリーン（AXFullScreen）だったか
+    var isFullscreen: Bool? = nil
+    /// ズーム（AXZoomed）状態だったか
+    var isZoomed: Bool? = nil
+    /// 保存時にどのディスプレイとも重ならない位置にあったか（ディスプレイ取り外し後の取り残し等）
+    var isOffscreen: Bool? = nil
+    /// ネイティブタブのタイトル（表示順）。タブを持たないウィンドウは nil
+    var tabTitles: [String]? = nil
+    /// 選択中タブの位置
+    var selectedTabIndex: Int? = nil
+    /// 最小化されていたか（AX取得時のみ。AX不可/旧データは nil または false）
+    var isMinimized: Bool? = nil
+    /// 所有アプリが非表示（Hide）だったか
+    var isHidden: Bool? = nil
+    /// 所有アプリがメニューバー常駐/エージェント（LSUIElement 等）か
+    var isAgentApp: Bool? = nil
+    /// 保存時に属していたディスプレイのフレーム（CG グローバル座標。ディスプレイ配置が変わった場合の座標変換に使用）
+    var displayBounds: CGRect? = nil
 }
 
+extension WindowInfo {
+    /// アプリ指定（bundle id の完全一致、またはアプリ名の大文字小文字を区別しない一致）のいずれかに該当するか
+    func belongs(toAnyOf apps: [String]) -> Bool {
+        return apps.contains { entry in
+            let pattern = entry.trimmingCharacters(in: .whitespacesAndNewlines)
+            if pattern.isEmpty { return false }
+            return bundleId == pattern || ownerName.caseInsensitiveCompare(pattern) == .orderedSame
+        }
+    }
+
+    /// ラベルのみ差し替えたコピーを返す
+    func withLabel(_ label: String) -> WindowInfo {
+        var copy = self
+        copy.layoutLabel = label
+        return copy
+    }
+}
+
+/// 復元処理における1ウィンドウ分の結果
+enum RestoreStatus {
+    /// 位置/サイズ（またはフルスクリーン状態）を復元した
+    case restored
+    /// 復元対象外（シート/ダイアログ、保存時に画面外）
+    case skipped
+    /// 対応するライブウィンドウが見つからない
+    case notFound
+    /// 位置/サイズの設定に失敗した
+    case failed
+    /// アプリがモーダルダイアログ/シートを表示していて操作できない
+    case blockedByDialog
+}
+
+/// 復元結果の集計
+struct RestoreReport {
+    /// インストールされておらず起動できなかったアプリ
+    struct MissingApp: Equatable {
+        let bundleId: String
+        /// 保存時のアプリ名
+        let name: String
+    }
+
+    var restored = 0
+    var skipped = 0
+    var notFound = 0
+    var failed = 0
+    var blockedByDialog = 0
+    var missingApps: [MissingApp] = []
+    /// オートメーション権限が無く AppleScript で配置できなかったアプリ（bundle id、不明な場合はアプリ名）
+    var automationDeniedApps: [String] = []
+    /// 制限時間（Config.restoreTimeoutMs）を超えて打ち切ったか（打ち切り後のウィンドウは集計に含まれない）
+    var timedOut = false
+    /// 保存時のディスプレイ構成との一致度（構成が記録されていないレイアウトは nil）
+    var displayMatch: DisplayMatch? = nil
+    /// 復元中にアクセシビリティ権限が取り消されたため打ち切ったか
+    var permissionRevoked = false
+
+    mutating func record(_ status: RestoreStatus) {
+        switch status {
+        case .restored: restored += 1
+        case .skipped: skipped += 1
+        case .notFound: notFound += 1
+        case .failed: failed += 1
+        case .blockedByDialog: blockedByDialog += 1
+        }
+    }
+}
+
+/// 復元時のオプション（未指定の項目は Config の既定値）
+struct RestoreOptions {
+    /// 未起動のアプリを起動するか（false の場合は起動中のアプリのウィンドウのみ復元）
+    var launchMissingApps: Bool
+    /// 復元対象のアプリ（bundle id またはアプリ名。nil の場合はすべて）
+    var apps: [String]? = nil
+    /// 復元対象のディスプレイ（保存時のディスプレイの UUID。nil の場合はすべて）
+    var displayUUID: String? = nil
+    /// 復元完了後にレイアウトのフォーカス対象アプリ（Layout.focusApp）をアクティブにするか
+    var activateFocusApp: Bool = true
+
+    init(launchMissingApps: Bool, apps: [String]? = nil) {
+        self.launchMissingApps = launchMissingApps
+        self.apps = apps
+    }
+
+    init(config: Config) {
+        self.init(launchMissingApps: config.launchMissingApps)
+    }
+}
+
+/// 復元の進捗通知（currentIndex は処理済み件数の 0 始まりの連番、ウィンドウを1件処理するごとに呼ばれる）
+/// - アプリ単位の並行復元のため、復元用のワーカースレッドから呼ばれる
+typealias RestoreProgressHandler = (_ currentIndex: Int, _ total: Int, _ window: WindowInfo, _ status: RestoreStatus) -> Void
+
 /// Swift単独のウィンドウ管理ロジック
 final class WindowManager {
     static let shared = WindowManager()
@@ -24,41 +145,316 @@ final class WindowManager {
         "Dock", "Window Server", "NotificationCenter", "Control Center", "Spotlight"
     ]
 
-    // 最小ウィンドウサイズ閾値
-    private let minWindowSize: CGFloat = 100.0
+    // 画面収録権限が無い旨の警告を出したか（スキャンのたびに出さないよう1回のみ）
+    private var didWarnScreenRecording = false
 
     // MARK: - 権限
 
     func hasAccessibilityPermission() -> Bool {
         // ダイアログを出さずに現在の状態のみを返す
-        return AXIsProcessTrusted()
+        return PermissionChecker.checkAccessibility()
+    }
+
+    /// 復元前の権限確認（ウィンドウに触れる前に呼ぶ）
+    /// - アクセシビリティは必須。不足していれば PermissionDeniedError
+    /// - オートメーションは AX で配置できなかった場合の AppleScript でのみ使い、送信先は System Events だけのため、
+    ///   System Events への権限が拒否されていても警告のみ
+    /// - 画面収録は保存時のタイトル取得にのみ使い、復元には不要
+    func preflight() throws {
+        guard hasAccessibilityPermission() else {
+            throw PermissionDeniedError(missing: [.accessibility])
+        }
+        if PermissionChecker.activeBackends().contains(.appleScript),
+           PermissionChecker.checkSystemEventsAutomation() == .denied {
+            Log.warning("[permission] System Events へのオートメーション権限が無いため、AX で配置できないウィンドウは復元されません")
+        }
+    }
+
+    /// アクセシビリティ権限が取り消されたときに投稿される通知（メインスレッド）
+    static let permissionRevokedNotification = Notification.Name("WindowManager.permissionRevoked")
+
+    // 権限の取り消しで一時停止した自動保存の間隔（メインスレッドでのみ参照）
+    private var pausedAutoSaveIntervalMs: UInt64?
+    private var isPausedForPermission = false
+
+    /// 実行中に権限が取り消されたときの処理
+    /// - 自動保存を一時停止し、ユーザーに通知する（同じ取り消しで通知するのは1回のみ）
+    /// - permissionRevokedNotification を投稿し、その他の常駐処理の停止は受け取り側で行う
+    func handlePermissionRevoked() {
+        DispatchQueue.main.async { [self] in
+            guard !isPausedForPermission else { return }
+            isPausedForPermission = true
+            PermissionAuditLog.record(.revoked, permission: .accessibility, result: "denied")
+            if let interval = autoSaveScheduler.intervalMs {
+                pausedAutoSaveIntervalMs = interval
+                stopAutoSave()
+            }
+            Log.error("[permission] アクセシビリティ権限が取り消されたため、常駐処理を一時停止しました")
+            NotificationManager.warn(title: "アクセシビリティ権限が取り消されました",
+                                     body: "ウィンドウの保存・復元を一時停止しました。システム設定で再度許可してください。")
+            NotificationCenter.default.post(name: WindowManager.permissionRevokedNotification, object: self)
+        }
+    }
+
+    /// 権限が再び許可されたときに、一時停止していた自動保存を再開
+    func resumeAfterPermissionGranted() {
+        DispatchQueue.main.async { [self] in
+            guard isPausedForPermission else { return }
+            isPausedForPermission = false
+            if let interval = pausedAutoSaveIntervalMs {
+                pausedAutoSaveIntervalMs = nil
+                startAutoSave(intervalMs: interval)
+            }
+            Log.info("[permission] アクセシビリティ権限が再び許可されたため、常駐処理を再開しました")
+        }
     }
 
     // MARK: - ウィンドウ取得
 
-    func fetchVisibleAppWindows() -> [WindowInfo] {
+    /// スキャン範囲
+    enum ScanMode {
+        /// 現在の Space の画面上ウィンドウのみ
+        case currentSpace
+        /// 全 Space（他 Space 上・画面外のウィンドウを含む）
+        case allSpaces
+    }
+
+    /// 現在のウィンドウ配列を取得
+    /// - CGWindowList でウィンドウを取得し、AX が使える場合は AXWindows でタイトル・最小化/非表示状態を補完する
+    /// - AX が使えない場合は CGWindowList の情報のみ（最小化/非表示ウィンドウは取得不可）
+    /// - `.allSpaces` では他 Space 上のウィンドウも含め、取得できる場合は Space ID を記録する
+    func fetchVisibleAppWindows(mode: ScanMode = .currentSpace) -> [WindowInfo] {
+        let windows = scanWindows(mode: mode, pids: nil)
+        storeCache(windows, mode: mode)
+        return windows
+    }
+
+    /// ウィンドウの作成/移動/リサイズ/クローズ等のイベント監視を開始
+    /// - 返された WindowWatcher を保持している間だけ通知される（破棄または stop() で停止）
+    /// - イベント受信時はスキャン結果キャッシュを破棄する
+    func watch(handler: @escaping (WindowEvent) -> Void) -> WindowWatcher {
+        let watcher = WindowWatcher { [weak self] event in
+            self?.invalidateCache()
+            handler(event)
+        }
+        watcher.start()
+        return watcher
+    }
+
+    /// ディスプレイの接続/取り外し/解像度変更などの監視を開始（ドック接続時の自動復元などに使う）
+    /// - 戻り値の DisplayWatcher を保持している間だけ通知される
+    /// - イベント受信時はスキャン結果キャッシュを破棄する
+    func watchDisplays(handler: @escaping (DisplayEvent) -> Void) -> DisplayWatcher {
+        let watcher = DisplayWatcher { [weak self] event in
+            self?.invalidateCache()
+            handler(event)
+        }
+        watcher.start()
+        return watcher
+    }
+
+    /// 設定ファイルの変更を監視（変更時はスキャン結果キャッシュを破棄し、ログの設定を反映してからハンドラを呼ぶ）
+    /// - 戻り値の ConfigWatcher を保持している間だけ監視する
+    func watchConfig(handler: @escaping (Config) -> Void) -> ConfigWatcher {
+        let watcher = ConfigWatcher { [weak self] config in
+            self?.invalidateCache()
+            Log.configure(with: config)
+            self?.scheduleLayoutEncryption(config: config)
+            handler(config)
+        }
+        watcher.start()
+        // アプリの停止中に設定が変更された場合に備えて起動時にも合わせる
+        scheduleLayoutEncryption(config: Config.load())
+        return watcher
+    }
+
+    // MARK: - 暗号化
+
+    private let encryptionQueue = DispatchQueue(label: "window_restore.encryption", qos: .utility)
+    // 最後に反映した encrypt_layouts（encryptionQueue でのみ参照）
+    private var appliedEncryptLayouts: Bool?
+
+    /// encrypt_layouts が前回反映した値から変わっていれば、保存済みのファイルをバックグラウンドで書き直す
+    func scheduleLayoutEncryption(config: Config) {
+        encryptionQueue.async { [self] in
+            guard appliedEncryptLayouts != config.encryptLayouts else { return }
+            applyLayoutEncryption(config: config)
+            appliedEncryptLayouts = config.encryptLayouts
+        }
+    }
+
+    /// 保存済みのレイアウト・バックアップ・テンプレート・取り消し用スナップショットを encrypt_layouts に合わせて書き直す
+    /// - 暗号化を有効にした後も、次に保存されるまで平文のファイルが残らないように（無効にした場合は平文に戻す）
+    func applyLayoutEncryption(config: Config = Config.load()) {
+        let encrypt = config.encryptLayouts
+        var rewritten = 0
+        do {
+            rewritten += try LayoutStores.store(for: config.storageBackend).applyEncryption(encrypt)
+        } catch {
+            Log.warning("[layout] レイアウトの暗号化設定の反映に失敗: \(error.localizedDescription)")
+        }
+        let templates = FileHelper.listTemplateNames().compactMap { try? FileHelper.templateFileURL(name: $0) }
+        for url in templates + [FileHelper.undoSnapshotURL()] {
+            do {
+                if try LayoutCrypto.rewriteJSONFile(at: url, encrypt: encrypt) { rewritten += 1 }
+            } catch {
+                Log.warning("[layout] 暗号化設定の反映に失敗 \(url.lastPathComponent): \(error.localizedDescription)")
+            }
+        }
+        if rewritten > 0 {
+            Log.info("[layout] 暗号化の設定（\(encrypt ? "有効" : "無効")）に合わせて \(rewritten) 件のファイルを書き直しました")
+        }
+    }
+
+    // MARK: - スキャン結果キャッシュ
+
+    private struct CachedScan {
+        let windows: [WindowInfo]
+        let capturedAt: Date
+    }
+
+    private var scanCache: [ScanMode: CachedScan] = [:]
+    private let scanCacheLock = NSLock()
+
+    /// キャッシュ付きのウィンドウ取得（プレビュー表示など頻繁な問い合わせ向け）
+    /// - Config.scanCacheTtlMs 以内の結果があればそれを返し、無ければスキャンしてキャッシュを更新する
+    func cachedWindows(mode: ScanMode = .currentSpace) -> [WindowInfo] {
+        let ttl = TimeInterval(Config.load().scanCacheTtlMs) / 1000.0
+        scanCacheLock.lock()
+        let cached = scanCache[mode]
+        scanCacheLock.unlock()
+        if let cached = cached, Date().timeIntervalSince(cached.capturedAt) < ttl {
+            return cached.windows
+        }
+        return fetchVisibleAppWindows(mode: mode)
+    }
+
+    /// キャッシュを破棄（復元直後など、ウィンドウ配置が変わったことが分かっている場合に呼ぶ）
+    func invalidateCache() {
+        scanCacheLock.lock()
+        scanCache.removeAll()
+        scanCacheLock.unlock()
+    }
+
+    private func storeCache(_ windows: [WindowInfo], mode: ScanMode) {
+        scanCacheLock.lock()
+        scanCache[mode] = CachedScan(windows: windows, capturedAt: Date())
+        scanCacheLock.unlock()
+    }
+
+    /// ウィンドウ配列を取得時刻とディスプレイ構成の指紋付きで取得
+    func captureSnapshot(mode: ScanMode = .currentSpace) -> Snapshot {
+        let windows = fetchVisibleAppWindows(mode: mode)
+        return Snapshot(windows: windows,
+                        capturedAt: Date(),
+                        displayFingerprint: DisplayManager.arrangementFingerprint())
+    }
+
+    // 非同期スキャン用のキュー（同時スキャンを直列化）
+    private let scanQueue = DispatchQueue(label: "window_restore.scan", qos: .userInitiated)
+
+    /// 非同期版のウィンドウ取得（GUIのメインスレッドをブロックしない）
+    /// - 同期版 `fetchVisibleAppWindows(mode:)` と同じ結果をバックグラウンドキューで取得する
+    func fetchVisibleAppWindowsAsync(mode: ScanMode = .currentSpace) async -> [WindowInfo] {
+        return await withCheckedContinuation { continuation in
+            scanQueue.async {
+                continuation.resume(returning: self.fetchVisibleAppWindows(mode: mode))
+            }
+        }
+    }
+
+    /// 指定アプリ（bundle id）のウィンドウのみ取得
+    /// - AX 走査を対象プロセスに限定するため、全体スキャンより軽量
+    /// - 対象アプリが起動していない場合は空配列
+    func fetchWindows(forBundleId bundleId: String, mode: ScanMode = .currentSpace) -> [WindowInfo] {
+        let pids = Set(NSRunningApplication.runningApplications(withBundleIdentifier: bundleId)
+            .filter { !$0.isTerminated }
+            .map { Int($0.processIdentifier) })
+        guard !pids.isEmpty else { return [] }
+        return scanWindows(mode: mode, pids: pids)
+    }
+
+    /// スキャン本体（pids 指定時はそのプロセスのウィンドウのみ対象）
+    private func scanWindows(mode: ScanMode, pids: Set<Int>?) -> [WindowInfo] {
+        let config = Config.load()
         // 複数回スナップショットを取り、安定して出現するウィンドウのみ採用
         let sampleCount = 3
         let sampleIntervalUs: useconds_t = 120_000
         var samples: [[RawWindow]] = []
         for _ in 0..<sampleCount {
-            samples.append(snapshotWindowsOnce())
+            let snapshot = snapshotWindowsOnce(mode: mode)
+            samples.append(pids.map { targets in snapshot.filter { targets.contains($0.pid) } } ?? snapshot)
             usleep(sampleIntervalUs)
         }
         let stabilized = consolidateWindows(samples: samples)
-        let filtered = filterWindows(from: stabilized)
-        return filtered.map { raw in
-            let displayUUID = resolveDisplayUUID(for: raw.bounds.origin)
+        // CGWindowList は前面→背面の順で返すため、その順位を重なり順として採用
+        let filtered = filterWindows(from: stabilized, config: config).sorted { $0.stackIndex < $1.stackIndex }
+        var order = 0
+        let captured = filtered.map { raw -> WindowInfo in
+            let displayUUID = DisplayManager.displayUUID(for: raw.bounds)
+            var zIndex: Int?
+            if raw.isOnScreen {
+                zIndex = order
+                order += 1
+            }
             return WindowInfo(
                 ownerName: raw.ownerName,
                 pid: raw.pid,
                 windowName: raw.windowName,
                 bounds: raw.bounds,
                 displayUUID: displayUUID,
-                spaceNumber: nil,
-                layoutLabel: nil
+                spaceNumber: SpaceHelper.spaceID(forWindowNumber: raw.windowNumber),
+                layoutLabel: nil,
+                bundleId: AppLauncher.bundleIdentifier(for: pid_t(raw.pid)),
+                windowNumber: raw.windowNumber,
+                zIndex: zIndex,
+                isMinimized: false,
+                isHidden: false
             )
         }
+        let windows = hasAccessibilityPermission()
+            ? sortedByStackingOrder(enrichWithAccessibility(captured, pids: pids, config: config))
+            : captured
+        // AX が使える場合はタイトルを AX から取得するため、画面収録権限は不要
+        if !didWarnScreenRecording && !hasAccessibilityPermission() && !PermissionChecker.checkScreenRecording() {
+            didWarnScreenRecording = true
+            Log.warning("[restore] 画面収録権限が無いため、ウィンドウタイトルを取得できない場合があります（システム設定 > プライバシーとセキュリティ > 画面収録）")
+        }
+        // 設定の除外リストに該当するアプリを除外
+        var agentCache: [Int: Bool] = [:]
+        return windows
+            .filter { !config.isExcluded(bundleId: $0.bundleId, ownerName: $0.ownerName) }
+            .map { w in
+                var info = w
+                info.isOffscreen = !DisplayManager.isOnAnyDisplay(w.bounds)
+                info.displayBounds = DisplayManager.displayID(for: w.bounds).map { CGDisplayBounds($0) }
+                if agentCache[w.pid] == nil {
+                    agentCache[w.pid] = AppLauncher.isAgentApplication(pid: pid_t(w.pid))
+                }
+                info.isAgentApp = agentCache[w.pid]
+                return info
+            }
+            // エージェントアプリは既定で除外（復元時に起動できずウィンドウも再現できないため）
+            .filter { config.includeAgentApps || $0.isAgentApp != true }
+    }
+
+    /// 前回スナップショットからの差分（追加/削除/移動）を取得
+    /// 戻り値: 差分と、次回比較用の今回スナップショット
+    func diff(previous: [WindowInfo], mode: ScanMode = .currentSpace) -> (diff: WindowDiff, current: [WindowInfo]) {
+        let current = fetchVisibleAppWindows(mode: mode)
+        return (WindowDiff.compute(previous: previous, current: current), current)
+    }
+
+    /// 重なり順（前面→背面）に並べ替え。zIndex を持たないウィンドウは末尾に元の順序で配置
+    func sortedByStackingOrder(_ windows: [WindowInfo]) -> [WindowInfo] {
+        return windows.enumerated().sorted { a, b in
+            switch (a.element.zIndex, b.element.zIndex) {
+            case let (za?, zb?): return za != zb ? za < zb : a.offset < b.offset
+            case (.some, .none): return true
+            case (.none, .some): return false
+            case (.none, .none): return a.offset < b.offset
+            }
+        }.map { $0.element }
     }
 
     // 内部表現（安定化のためにwindowNumber等を保持）
@@ -70,15 +466,23 @@ final class WindowManager {
         let layer: Int
         let alpha: CGFloat
         let windowNumber: Int
+        // CGWindowList 内の位置（小さいほど前面）
+        let stackIndex: Int
+        // 現在の Space で画面上に表示されているか
+        let isOnScreen: Bool
     }
 
-    private func snapshotWindowsOnce() -> [RawWindow] {
-        let options: CGWindowListOption = [.optionOnScreenOnly, .excludeDesktopElements]
+    private func snapshotWindowsOnce(mode: ScanMode) -> [RawWindow] {
+        let options: CGWindowListOption
+        switch mode {
+        case .currentSpace: options = [.optionOnScreenOnly, .excludeDesktopElements]
+        case .allSpaces: options = [.optionAll, .excludeDesktopElements]
+        }
         guard let infoList = CGWindowListCopyWindowInfo(options, kCGNullWindowID) as? [[String: Any]] else {
             return []
         }
         var result: [RawWindow] = []
-        for dict in infoList {
+        for (stackIndex, dict) in infoList.enumerated() {
             guard
                 let ownerName = dict[kCGWindowOwnerName as String] as? String,
                 let pid = dict[kCGWindowOwnerPID as String] as? Int,
@@ -98,6 +502,7 @@ final class WindowManager {
             let rect = CGRect(x: x, y: y, width: w, height: h)
 
             let windowName = dict[kCGWindowName as String] as? String
+            let isOnScreen = mode == .currentSpace || ((dict[kCGWindowIsOnscreen as String] as? Bool) ?? false)
 
             result.append(RawWindow(ownerName: ownerName,
                                     pid: pid,
@@ -105,7 +510,9 @@ final class WindowManager {
                                     bounds: rect,
                                     layer: layer,
                                     alpha: alpha,
-                                    windowNumber: windowNumber))
+                                    windowNumber: windowNumber,
+                                    stackIndex: stackIndex,
+                                    isOnScreen: isOnScreen))
         }
         return result
     }
@@ -132,86 +539,996 @@ final class WindowManager {
     }
 
     // 表示用に安定フィルタのみ（同一アプリの複数ウィンドウを保持）
-    private func filterWindows(from windows: [RawWindow]) -> [RawWindow] {
+    private func filterWindows(from windows: [RawWindow], config: Config) -> [RawWindow] {
         return windows.filter { w in
             if excludedOwnerNames.contains(w.ownerName) { return false }
-            if w.layer != 0 { return false }
-            if w.alpha < 0.01 { return false }
-            if w.bounds.width < minWindowSize || w.bounds.height < minWindowSize { return false }
+            if config.skipInvisibleWindows && !isUserVisibleWindow(w, config: config) { return false }
+            if !config.meetsMinimumSize(w.bounds) { return false }
+            if !w.isOnScreen && !isUserOffscreenWindow(w) { return false }
+            // 仮想ディスプレイ上のウィンドウは一時的な配置のため記録しない
+            if config.excludeVirtualDisplays, let id = DisplayManager.displayID(for: w.bounds),
+               DisplayManager.isVirtualDisplay(id) { return false }
             return true
         }
     }
 
-    private func resolveDisplayUUID(for globalPoint: CGPoint) -> String? {
-        for screen in NSScreen.screens {
-            if screen.frame.contains(globalPoint) {
-                if let displayId = (screen.deviceDescription[NSDeviceDescriptionKey("NSScreenNumber")] as? NSNumber)?.uint32Value {
-                    if let uuidRef = CGDisplayCreateUUIDFromDisplayID(displayId)?.takeRetainedValue(),
-                       let cfStr = CFUUIDCreateString(kCFAllocatorDefault, uuidRef) {
-                        return cfStr as String
-                    } else {
-                        return String(displayId)
+    // 透明オーバーレイ/ステータスアイテム等の通常レイヤー以外/バックグラウンド専用プロセスのウィンドウでないか
+    private func isUserVisibleWindow(_ w: RawWindow, config: Config) -> Bool {
+        if w.layer != 0 { return false }
+        if Double(w.alpha) < config.minWindowAlpha { return false }
+        if NSRunningApplication(processIdentifier: pid_t(w.pid))?.activationPolicy == .prohibited { return false }
+        return true
+    }
+
+    // 画面外ウィンドウ（全Spaceスキャン時）のうちユーザーが扱うものか
+    // - 通常アプリ所有であること、Space 情報が取れる環境では何らかの Space に属していること
+    private func isUserOffscreenWindow(_ w: RawWindow) -> Bool {
+        guard NSRunningApplication(processIdentifier: pid_t(w.pid))?.activationPolicy == .regular else { return false }
+        guard SpaceHelper.isAvailable else { return true }
+        return SpaceHelper.spaceID(forWindowNumber: w.windowNumber) != nil
+    }
+
+    // MARK: - AX による補完
+
+    // CGウィンドウとAXウィンドウのフレーム一致とみなす許容誤差（px）
+    private let axFrameTolerance: CGFloat = 4.0
+
+    /// AXWindows を走査してタイトル・最小化/非表示状態を補完し、画面外（最小化/非表示）のウィンドウを追加
+    /// - targetPids 指定時はそのプロセスのみ走査する
+    private func enrichWithAccessibility(_ windows: [WindowInfo], pids targetPids: Set<Int>? = nil, config: Config) -> [WindowInfo] {
+        let byPid = Dictionary(grouping: windows, by: { $0.pid })
+        let selfPid = Int(ProcessInfo.processInfo.processIdentifier)
+
+        // 画面上にウィンドウを持つプロセス + 通常アプリ（最小化/非表示のみのアプリを拾うため）
+        var pids = Set(byPid.keys)
+        if let targetPids = targetPids {
+            pids.formUnion(targetPids)
+        } else {
+            for app in NSWorkspace.shared.runningApplications where app.activationPolicy == .regular {
+                pids.insert(Int(app.processIdentifier))
+            }
+        }
+        pids.remove(selfPid)
+
+        var result: [WindowInfo] = []
+        for pid in pids.sorted() {
+            let cgWindows = byPid[pid] ?? []
+            guard let axWindows = AccessibilityHelper.windowSnapshots(pid: pid_t(pid)) else {
+                // AX非対応アプリは CGWindowList の情報をそのまま採用
+                result.append(contentsOf: cgWindows)
+                continue
+            }
+            let runningApp = NSRunningApplication(processIdentifier: pid_t(pid))
+            let isHidden = runningApp?.isHidden ?? false
+            var unmatched = axWindows
+
+            for cg in cgWindows {
+                var info = cg
+                info.isHidden = isHidden
+                // 画面外のCGウィンドウ（全Spaceスキャン時）は最小化ウィンドウとも照合する
+                let isOffscreen = cg.zIndex == nil
+                if let index = closestAXWindowIndex(to: cg.bounds, in: unmatched, includeMinimized: isOffscreen) {
+                    let ax = unmatched.remove(at: index)
+                    info.isMinimized = ax.isMinimized
+                    info.axIdentifier = ax.identifier
+                    info.axRole = ax.role
+                    info.axSubrole = ax.subrole
+                    info.isFullscreen = ax.isFullscreen
+                    info.isZoomed = ax.isZoomed
+                    info.tabTitles = ax.tabTitles
+                    info.selectedTabIndex = ax.selectedTabIndex
+                    if let title = ax.title, !title.isEmpty {
+                        info.windowName = title
                     }
                 }
+                result.append(info)
+            }
+
+            // 画面上に無い最小化/非表示ウィンドウを追加
+            let ownerName = cgWindows.first?.ownerName ?? runningApp?.localizedName ?? ""
+            if ownerName.isEmpty || excludedOwnerNames.contains(ownerName) { continue }
+            var appended = result.filter { $0.pid == pid }
+            for ax in unmatched where ax.isMinimized || isHidden {
+                if !config.meetsMinimumSize(ax.frame) { continue }
+                // 同じタブグループの別タブ（同一フレームで同じタブ構成）は1ウィンドウとして数える
+                if ax.tabTitles != nil,
+                   appended.contains(where: { $0.tabTitles == ax.tabTitles && $0.bounds.isApproximatelyEqual(to: ax.frame, tolerance: axFrameTolerance) }) {
+                    continue
+                }
+                let info = WindowInfo(ownerName: ownerName,
+                                      pid: pid,
+                                      windowName: ax.title,
+                                      bounds: ax.frame,
+                                      displayUUID: DisplayManager.displayUUID(for: ax.frame),
+                                      spaceNumber: nil,
+                                      layoutLabel: nil,
+                                      bundleId: runningApp?.bundleIdentifier,
+                                      axIdentifier: ax.identifier,
+                                      axRole: ax.role,
+                                      axSubrole: ax.subrole,
+                                      isFullscreen: ax.isFullscreen,
+                                      isZoomed: ax.isZoomed,
+                                      tabTitles: ax.tabTitles,
+                                      selectedTabIndex: ax.selectedTabIndex,
+                                      isMinimized: ax.isMinimized,
+                                      isHidden: isHidden)
+                appended.append(info)
+                result.append(info)
             }
         }
-        return nil
+        return result
+    }
+
+    private func closestAXWindowIndex(to bounds: CGRect,
+                                      in candidates: [AccessibilityHelper.WindowSnapshot],
+                                      includeMinimized: Bool = false) -> Int? {
+        var best: (index: Int, distance: CGFloat)?
+        for (i, ax) in candidates.enumerated() where includeMinimized || !ax.isMinimized {
+            let distance = abs(ax.frame.minX - bounds.minX) + abs(ax.frame.minY - bounds.minY)
+                + abs(ax.frame.width - bounds.width) + abs(ax.frame.height - bounds.height)
+            if distance <= axFrameTolerance * 4, distance < (best?.distance ?? .greatestFiniteMagnitude) {
+                best = (i, distance)
+            }
+        }
+        return best?.index
     }
 
     // MARK: - JSON 保存/読み込み
 
-    func saveWindows(name: String) throws {
-        try FileHelper.ensureDirectories()
-        let info = fetchVisibleAppWindows()
-        let url = try FileHelper.layoutFileURL(name: name)
-        try FileHelper.saveJSON(info, to: url)
+    func saveWindows(name: String, mode: ScanMode = .currentSpace) throws {
+        try checkNotReserved(name)
+        try captureWindows(into: name, mode: mode)
+    }
+
+    private func captureWindows(into name: String, mode: ScanMode) throws {
+        let info = fetchVisibleAppWindows(mode: mode)
+        let displays = DisplayManager.currentDisplays()
+        try updateLayout(name: name) { layout in
+            layout.windows = info
+            layout.displays = displays
+        }
+        pruneLayouts(keeping: name)
+    }
+
+    /// 既存レイアウト内の指定ディスプレイのウィンドウのみ現在の状態で置き換え（レイアウトが無ければ作成）
+    /// - 他のディスプレイに保存されたウィンドウはそのまま残す
+    func saveWindowsForDisplay(name: String, displayUUID uuid: String, mode: ScanMode = .currentSpace) throws {
+        try checkNotReserved(name)
+        guard let displayId = DisplayManager.displayID(forUUID: uuid) else {
+            throw NSError(domain: "WindowManager", code: 2, userInfo: [NSLocalizedDescriptionKey: "ディスプレイが接続されていません: \(uuid)"])
+        }
+        let display = DisplayManager.displayInfo(for: displayId)
+        let onDisplay = { (w: WindowInfo) in w.displayUUID?.caseInsensitiveCompare(display.uuid) == .orderedSame }
+        let captured = fetchVisibleAppWindows(mode: mode).filter(onDisplay)
+        let current = DisplayManager.currentDisplays()
+        try updateLayout(name: name) { layout in
+            layout.windows = layout.windows.filter { !onDisplay($0) } + captured
+            // 他のディスプレイは保存時の記録を残し、このディスプレイの記録だけ更新する
+            if var displays = layout.displays {
+                displays.removeAll { $0.uuid.caseInsensitiveCompare(display.uuid) == .orderedSame }
+                displays.append(display)
+                layout.displays = displays
+            } else {
+                layout.displays = current
+            }
+        }
+        pruneLayouts(keeping: name)
+    }
+
+    /// 既存レイアウト内の指定アプリのウィンドウのみ現在の状態で置き換え
+    func refreshWindows(name: String, bundleId: String) throws {
+        let captured = fetchWindows(forBundleId: bundleId)
+        try updateLayout(name: name) { layout in
+            layout.windows = layout.windows.filter { $0.bundleId != bundleId } + captured
+        }
     }
 
     func loadWindows(name: String) throws -> [WindowInfo] {
-        let url = try FileHelper.layoutFileURL(name: name)
-        return try FileHelper.loadJSON([WindowInfo].self, from: url)
+        return try loadLayout(name: name).windows
+    }
+
+    /// レイアウトの概要（アプリごとのウィンドウ数、日時、メタデータ）
+    func layoutSummary(name: String) throws -> LayoutSummary {
+        return LayoutSummary(try loadLayout(name: name))
+    }
+
+    /// すべてのレイアウトの概要（読み込めないレイアウトは除く）
+    func layoutSummaries() -> [LayoutSummary] {
+        return listLayouts().compactMap { try? layoutSummary(name: $0) }
+    }
+
+    /// レイアウトの配置の模式的なプレビュー
+    func layoutPreview(name: String) throws -> LayoutPreview {
+        return LayoutPreview(try loadLayout(name: name))
+    }
+
+    /// 現在と同じディスプレイ配置で保存されたレイアウト（最近使った順。自動スナップショットは後ろ）
+    func layoutsMatchingCurrentDisplays() -> [String] {
+        let current = DisplayManager.arrangementFingerprint()
+        let matching = listLayouts().compactMap { name -> (name: String, usedAt: Date)? in
+            guard let layout = try? loadLayout(name: name), layout.displayArrangement == current else { return nil }
+            return (name, lastUsedDate(of: layout, name: name))
+        }
+        return matching.sorted { a, b in
+            let autoA = isAutoSnapshot(a.name), autoB = isAutoSnapshot(b.name)
+            if autoA != autoB { return !autoA }
+            return a.usedAt > b.usedAt
+        }.map(\.name)
+    }
+
+    /// 名前・説明・タグ・含まれるアプリ名/bundle id で検索したレイアウト名
+    func findLayouts(matching query: String) -> [String] {
+        return listLayouts().filter { name in
+            (try? loadLayout(name: name))?.matches(query: query) ?? false
+        }
+    }
+
+    /// 設定で選択された保存先
+    var layoutStore: LayoutStore {
+        return LayoutStores.store(for: Config.load().storageBackend)
+    }
+
+    func loadLayout(name: String) throws -> Layout {
+        return try layoutStore.load(name: name)
+    }
+
+    /// レイアウトを変更して保存（存在しない場合は新規作成。作成日時などのメタデータは保持する）
+    /// - 既存のレイアウトを読み込めない場合（破損・復号の失敗など）は上書きせずにエラーを投げる
+    func updateLayout(name: String, _ modify: (inout Layout) throws -> Void) throws {
+        try FileHelper.ensureDirectories()
+        var layout: Layout
+        do {
+            layout = try loadLayout(name: name)
+        } catch FileHelper.FileHelperError.layoutNotFound {
+            layout = Layout(name: name, windows: [])
+        }
+        try modify(&layout)
+        layout.updatedAt = Date()
+        try writeLayout(layout, name: name)
+    }
+
+    // 上書き前の内容を世代バックアップへ退避してから保存
+    private func writeLayout(_ layout: Layout, name: String) throws {
+        let config = Config.load()
+        try LayoutStores.store(for: config.storageBackend).save(layout, name: name, keepBackups: config.layoutBackupCount)
+    }
+
+    /// レイアウトのバックアップ世代（1 が最新）
+    func layoutBackupGenerations(name: String) -> [Int] {
+        return layoutStore.backupGenerations(name: name)
+    }
+
+    /// バックアップ世代の内容でレイアウトを戻す（現在の内容は新しいバックアップとして残る）
+    func restoreLayoutBackup(name: String, generation: Int) throws {
+        var layout = try layoutStore.loadBackup(name: name, generation: generation)
+        // 旧形式のバックアップは日時を持たないため現在のレイアウトの作成日時を引き継ぐ
+        if layout.createdAt == .distantPast {
+            layout.createdAt = (try? loadLayout(name: name))?.createdAt ?? Date()
+        }
+        layout.updatedAt = Date()
+        try writeLayout(layout, name: name)
+    }
+
+    /// レイアウトの説明・タグ・ショートカットを取得
+    func layoutMetadata(name: String) throws -> LayoutMetadata {
+        return try loadLayout(name: name).metadata
+    }
+
+    /// レイアウトの説明・タグ・ショートカットを更新（ウィンドウ一覧はそのまま）
+    func updateLayoutMetadata(name: String, _ metadata: LayoutMetadata) throws {
+        _ = try loadLayout(name: name)
+        try updateLayout(name: name) { $0.metadata = metadata }
+    }
+
+    /// 復元完了後にアクティブにするアプリを設定（nil で解除）
+    func setFocusApp(name: String, bundleId: String?) throws {
+        _ = try loadLayout(name: name)
+        try updateLayout(name: name) { $0.focusApp = bundleId }
     }
 
     func listLayouts() -> [String] {
-        return FileHelper.listLayoutNames()
+        return layoutStore.listNames()
     }
 
     func deleteLayout(name: String) throws {
-        try FileHelper.deleteLayout(name: name)
+        try layoutStore.delete(name: name)
+        LayoutUsageIndex.remove(name)
+    }
+
+    /// レイアウト名を変更（作成日時などのメタデータとバックアップ世代は引き継ぐ）
+    /// - 新しい名前が不正/既存レイアウトと重複する場合はエラー
+    func renameLayout(from oldName: String, to newName: String) throws {
+        let trimmed = newName.trimmingCharacters(in: .whitespacesAndNewlines)
+        guard FileHelper.validateLayoutName(trimmed) else { throw FileHelper.FileHelperError.invalidLayoutName }
+        guard trimmed != oldName else { return }
+        try checkNotReserved(trimmed)
+        guard !listLayouts().contains(trimmed) else { throw FileHelper.FileHelperError.layoutAlreadyExists }
+        let store = layoutStore
+        var layout = try store.load(name: oldName)
+        try store.transaction {
+            try store.rename(from: oldName, to: trimmed)
+            // 保存内容の名前も更新（バックアップは作らない）
+            layout.name = trimmed
+            layout.updatedAt = Date()
+            try store.save(layout, name: trimmed, keepBackups: 0)
+        }
+        LayoutUsageIndex.rename(from: oldName, to: trimmed)
+    }
+
+    /// 2つのレイアウトを合成して保存（保存先を省略した場合は base を上書き）
+    func mergeLayouts(base: String, overlay: String, strategy: LayoutMergeStrategy, into target: String? = nil) throws {
+        let merged = try loadLayout(name: base).merged(with: try loadLayout(name: overlay), strategy: strategy)
+        let name = target ?? base
+        if target != nil { try checkNotReserved(name) }
+        try updateLayout(name: name) { layout in
+            if name == base {
+                layout = merged
+            } else {
+                layout.windows = merged.windows
+                layout.focusApp = merged.focusApp
+            }
+        }
+        Log.info("[layout] \(base) + \(overlay) を合成しました → \(name)（\(merged.windows.count) ウィンドウ）")
+    }
+
+    // MARK: - 保存数の上限
+
+    /// 上限超過時の自動削除の対象外にするか
+    func setLayoutPinned(name: String, pinned: Bool) throws {
+        var layout = try loadLayout(name: name)
+        layout.pinned = pinned
+        // メタデータのみの変更のためバックアップは作らない
+        try layoutStore.save(layout, name: name, keepBackups: 0)
+    }
+
+    // 最終使用日時を記録（レイアウトファイルは書き換えない）
+    private func markLayoutUsed(_ name: String) {
+        LayoutUsageIndex.markUsed(name)
+    }
+
+    // 最終使用日時（未使用なら更新日時）
+    private func lastUsedDate(of layout: Layout, name: String) -> Date {
+        return LayoutUsageIndex.lastUsed(name) ?? layout.lastUsedAt ?? layout.updatedAt
+    }
+
+    /// レイアウト数が上限（Config.maxLayouts）を超えていれば、固定していないものを使われていない順に削除
+    /// - 自動スナップショットを先に削除する
+    /// - keeping: 直前に保存したレイアウトなど、削除しない名前
+    /// 戻り値: 削除したレイアウト名
+    @discardableResult
+    func pruneLayouts(keeping protected: String? = nil) -> [String] {
+        let limit = Config.load().maxLayouts
+        let names = listLayouts()
+        guard limit > 0, names.count > limit else { return [] }
+
+        let candidates = names.compactMap { name -> (name: String, auto: Bool, lastUsed: Date)? in
+            guard name != protected, let layout = try? loadLayout(name: name), !layout.pinned else { return nil }
+            return (name, isAutoSnapshot(name), lastUsedDate(of: layout, name: name))
+        }.sorted { a, b in
+            if a.auto != b.auto { return a.auto }
+            return a.lastUsed < b.lastUsed
+        }
+
+        var removed: [String] = []
+        for candidate in candidates.prefix(names.count - limit) {
+            do {
+                try deleteLayout(name: candidate.name)
+                removed.append(candidate.name)
+            } catch {
+                Log.warning("[layout] レイアウトの削除に失敗 \(candidate.name): \(error.localizedDescription)")
+            }
+        }
+        if !removed.isEmpty {
+            Log.info("[layout] 保存数の上限（\(limit)）を超えたため削除しました: \(removed.joined(separator: ", "))")
+        }
+        return removed
+    }
+
+    // MARK: - 自動スナップショット
+
+    /// 自動スナップショットのレイアウト名の接頭辞（"auto_2024-05-01_10-00-00"）
+    static let autoSnapshotPrefix = "auto_"
+
+    // レイアウト名に使えない ":" を避けた日時表記（ローカル時刻）
+    private static let autoSnapshotDateFormatter: DateFormatter = {
+        let formatter = DateFormatter()
+        formatter.locale = Locale(identifier: "en_US_POSIX")
+        formatter.dateFormat = "yyyy-MM-dd_HH-mm-ss"
+        return formatter
+    }()
+
+    /// 自動スナップショットの名前か（"auto_<日時>"、同じ秒の連番付き "auto_<日時> (2)" のみ。"auto_work" などは含まない）
+    func isAutoSnapshot(_ name: String) -> Bool {
+        return autoSnapshotDate(name) != nil
+    }
+
+    /// 自動スナップショット用に予約された名前か（手動での保存・名前変更では使えない）
+    func isReservedLayoutName(_ name: String) -> Bool {
+        return name.hasPrefix(Self.autoSnapshotPrefix)
+    }
+
+    private func checkNotReserved(_ name: String) throws {
+        if isReservedLayoutName(name.trimmingCharacters(in: .whitespacesAndNewlines)) {
+            throw FileHelper.FileHelperError.reservedLayoutName
+        }
+    }
+
+    /// 自動スナップショットのレイアウト名（古い順）
+    func listAutoSnapshots() -> [String] {
+        // 日時表記は辞書順 = 時刻順
+        return listLayouts().filter(isAutoSnapshot).sorted()
+    }
+
+    /// 現在のウィンドウ配置を日時付きの名前で保存し、保持ポリシーに従って古いものを削除
+    /// 戻り値: 保存したレイアウト名
+    @discardableResult
+    func saveAutoSnapshot(mode: ScanMode = .currentSpace) throws -> String {
+        var name = Self.autoSnapshotPrefix + Self.autoSnapshotDateFormatter.string(from: Date())
+        // 同じ秒に複数回保存された場合は連番を付ける
+        if listLayouts().contains(name) {
+            name = uniqueLayoutName(base: name, existing: Set(listLayouts()))
+        }
+        try captureWindows(into: name, mode: mode)
+        Log.info("[layout] 自動スナップショットを保存しました: \(name)")
+        pruneAutoSnapshots()
+        return name
+    }
+
+    /// 保持件数（Config.autoSnapshotKeep）と保持日数（Config.autoSnapshotMaxAgeDays）を超えた自動スナップショットを削除
+    /// 戻り値: 削除したレイアウト名
+    @discardableResult
+    func pruneAutoSnapshots(now: Date = Date()) -> [String] {
+        let config = Config.load()
+        let snapshots = listAutoSnapshots()
+        var expired = Set<String>()
+        if config.autoSnapshotKeep > 0, snapshots.count > config.autoSnapshotKeep {
+            expired.formUnion(snapshots.prefix(snapshots.count - config.autoSnapshotKeep))
+        }
+        if config.autoSnapshotMaxAgeDays > 0 {
+            let cutoff = now.addingTimeInterval(-Double(config.autoSnapshotMaxAgeDays) * 86_400)
+            for name in snapshots where autoSnapshotDate(name).map({ $0 < cutoff }) ?? false {
+                expired.insert(name)
+            }
+        }
+        var removed: [String] = []
+        for name in snapshots where expired.contains(name) {
+            do {
+                try deleteLayout(name: name)
+                removed.append(name)
+            } catch {
+                Log.warning("[layout] 自動スナップショットの削除に失敗 \(name): \(error.localizedDescription)")
+            }
+        }
+        if !removed.isEmpty {
+            Log.info("[layout] 古い自動スナップショットを \(removed.count) 件削除しました")
+        }
+        return removed
+    }
+
+    // 名前から保存日時を取得（自動スナップショットの名前の形式でなければ nil）
+    private func autoSnapshotDate(_ name: String) -> Date? {
+        guard name.hasPrefix(Self.autoSnapshotPrefix) else { return nil }
+        let rest = name.dropFirst(Self.autoSnapshotPrefix.count)
+        let suffix = rest.dropFirst(19)
+        guard suffix.isEmpty || suffix.range(of: #"^ \(\d+\)$"#, options: .regularExpression) != nil else { return nil }
+        return Self.autoSnapshotDateFormatter.date(from: String(rest.prefix(19)))
+    }
+
+    // MARK: - 自動保存
+
+    private let autoSaveScheduler = AutoSaveScheduler()
+
+    /// 一定間隔（省略時は Config.scanIntervalMs）での自動スナップショットを開始（実行中の場合は開始し直す）
+    func startAutoSave(intervalMs: UInt64? = nil) {
+        autoSaveScheduler.start(intervalMs: intervalMs)
+    }
+
+    func stopAutoSave() {
+        autoSaveScheduler.stop()
+    }
+
+    var isAutoSaveRunning: Bool {
+        return autoSaveScheduler.isRunning
+    }
+
+    // MARK: - 同期フォルダの競合解決
+
+    /// 同期フォルダ（iCloud Drive など）で生じた競合を後勝ちで解決する
+    /// - iCloud の未解決バージョン（NSFileVersion）と、同期サービスが作る競合コピーの両方を対象にする
+    /// - 負けた側の内容はバックアップ世代として残す（競合コピーが古い場合は削除）
+    /// 戻り値: 競合を解決したレイアウト名
+    @discardableResult
+    func resolveSyncConflicts() -> [String] {
+        let config = Config.load()
+        guard config.storageBackend == .file else { return [] }
+        var resolved: [String] = []
+
+        for name in listLayouts() {
+            guard let url = try? FileHelper.layoutFileURL(name: name, config: config),
+                  let conflicts = NSFileVersion.unresolvedConflictVersionsOfItem(at: url),
+                  !conflicts.isEmpty else { continue }
+            let currentDate = NSFileVersion.currentVersionOfItem(at: url)?.modificationDate ?? .distantPast
+            let newest = conflicts.max { ($0.modificationDate ?? .distantPast) < ($1.modificationDate ?? .distantPast) }
+            do {
+                if let newest = newest, (newest.modificationDate ?? .distantPast) > currentDate {
+                    try FileHelper.rotateBackups(of: url, keep: config.layoutBackupCount)
+                    try newest.replaceItem(at: url, options: [])
+                }
+                conflicts.forEach { $0.isResolved = true }
+                try NSFileVersion.removeOtherVersionsOfItem(at: url)
+                resolved.append(name)
+            } catch {
+                Log.warning("[layout] 同期競合の解決に失敗 \(name): \(error.localizedDescription)")
+            }
+        }
+
+        for (copyURL, name) in FileHelper.conflictCopies() {
+            do {
+                var copy = try LayoutCrypto.decode(Data(contentsOf: copyURL), format: SerializationFormat(url: copyURL) ?? .json)
+                let current = try? loadLayout(name: name)
+                if current.map({ copy.updatedAt > $0.updatedAt }) ?? true {
+                    copy.name = name
+                    try writeLayout(copy, name: name)
+                }
+                try FileManager.default.removeItem(at: copyURL)
+                resolved.append(name)
+            } catch {
+                Log.warning("[layout] 競合コピーの取り込みに失敗 \(copyURL.lastPathComponent): \(error.localizedDescription)")
+            }
+        }
+
+        if !resolved.isEmpty {
+            Log.info("[layout] 同期競合を解決しました: \(resolved.joined(separator: ", "))")
+        }
+        return resolved
+    }
+
+    // MARK: - エクスポート/インポート
+
+    /// 指定レイアウトを1つのアーカイブファイルへ書き出す
+    func exportLayouts(names: [String], to url: URL) throws {
+        let layouts = try names.map { try loadLayout(name: $0) }
+        try FileHelper.saveJSON(LayoutArchive(layouts: layouts), to: url)
+        Log.info("[layout] \(layouts.count) 件のレイアウトをエクスポートしました: \(url.path)")
+    }
+
+    /// アーカイブファイルからレイアウトを取り込む
+    /// 戻り値: 保存したレイアウト名（別名で取り込んだ場合はその名前）
+    @discardableResult
+    func importLayouts(from url: URL, onConflict policy: ImportConflictPolicy = .rename) throws -> [String] {
+        let archive = try FileHelper.loadJSON(LayoutArchive.self, from: url)
+        let store = layoutStore
+        let keep = Config.load().layoutBackupCount
+        var existing = Set(store.listNames())
+        var imported: [String] = []
+        // 途中で失敗した場合、対応する保存先では取り込み全体を取り消す
+        try store.transaction {
+            for var layout in archive.layouts {
+                let original = layout.name.trimmingCharacters(in: .whitespacesAndNewlines)
+                guard FileHelper.validateLayoutName(original) else {
+                    Log.warning("[layout] 不正なレイアウト名のためスキップ: \(layout.name)")
+                    continue
+                }
+                var name = original
+                if existing.contains(name) {
+                    switch policy {
+                    case .skip:
+                        Log.info("[layout] 同名のレイアウトが存在するためスキップ: \(name)")
+                        continue
+                    case .overwrite:
+                        break
+                    case .rename:
+                        name = uniqueLayoutName(base: original, existing: existing)
+                    }
+                }
+                layout.name = name
+                try store.save(layout, name: name, keepBackups: keep)
+                existing.insert(name)
+                imported.append(name)
+            }
+        }
+        Log.info("[layout] \(imported.count)/\(archive.layouts.count) 件のレイアウトをインポートしました")
+        return imported
+    }
+
+    // "名前 (2)", "名前 (3)" ... のうち未使用のもの
+    private func uniqueLayoutName(base: String, existing: Set<String>) -> String {
+        var n = 2
+        while existing.contains("\(base) (\(n))") { n += 1 }
+        return "\(base) (\(n))"
     }
 
     // MARK: - 復元
 
-    func restoreWindows(name: String) throws {
-        guard hasAccessibilityPermission() else {
-            throw NSError(domain: "WindowManager", code: 1, userInfo: [NSLocalizedDescriptionKey: "アクセシビリティ権限が必要です"])
+    // フルスクリーン切り替えアニメーションの完了待ち（マイクロ秒）
+    private let fullscreenTransitionDelay: useconds_t = 800_000
+
+    /// レイアウトを復元
+    /// - options: 省略時は Config の既定値
+    /// - progress: ウィンドウを1件処理するごとに呼ばれる（ワーカースレッドから呼ばれる）
+    @discardableResult
+    func restoreWindows(name: String, options: RestoreOptions? = nil, progress: RestoreProgressHandler? = nil) throws -> RestoreReport {
+        try preflight()
+        let layout = try loadLayout(name: name)
+        let displayMatch = layout.displayMatch()
+        warnIfDisplaysChanged(displayMatch, layout: layout, layoutName: name)
+        saveUndoSnapshot()
+        markLayoutUsed(name)
+        var report = restoreWindowList(layout.windows, options: options, progress: progress)
+        if report.permissionRevoked { throw PermissionDeniedError(missing: [.accessibility]) }
+        report.displayMatch = displayMatch
+        // 指定されたアプリを最後にアクティブにし、作業を続けるアプリを前面に出す
+        if let focusApp = layout.focusApp, options?.activateFocusApp ?? true {
+            if let app = AppLauncher.runningApplication(bundleId: focusApp) {
+                app.activate(options: [.activateIgnoringOtherApps])
+            } else {
+                Log.info("[restore] フォーカス対象のアプリが起動していません: \(focusApp)")
+            }
+        }
+        return report
+    }
+
+    // 保存時のモニターが接続されていない場合に通知（配置・解像度の違いは座標換算で吸収するため通知しない）
+    private func warnIfDisplaysChanged(_ match: DisplayMatch?, layout: Layout, layoutName: String) {
+        switch match {
+        case .partial(let missing):
+            // 取り外されたディスプレイは別名、または保存時の名前で示す
+            let config = Config.load()
+            let labels = missing.map { uuid in
+                layout.displays?.first { $0.uuid == uuid }?.label(config: config) ?? DisplayManager.label(forUUID: uuid, config: config)
+            }
+            Log.warning("[restore] 保存時のディスプレイの一部が接続されていません: \(labels.joined(separator: ", "))")
+            NotificationManager.warn(title: "ディスプレイ構成が異なります",
+                                     body: "「\(layoutName)」の保存時に接続されていた \(labels.joined(separator: "、")) が見つかりません。一部のウィンドウは別のディスプレイに配置されます。")
+        case .different:
+            Log.warning("[restore] 保存時のディスプレイが接続されていません")
+            NotificationManager.warn(title: "ディスプレイ構成が異なります",
+                                     body: "「\(layoutName)」の保存時に接続されていたディスプレイが見つかりません。ウィンドウは現在のディスプレイ内に配置されます。")
+        case .rearranged:
+            Log.info("[restore] ディスプレイの配置または解像度が保存時と異なるため座標を換算します")
+        case .identical, nil:
+            break
+        }
+    }
+
+    /// 保存時と現在のディスプレイ構成の一致度（構成が記録されていない場合は nil）
+    func layoutDisplayMatch(name: String) throws -> DisplayMatch? {
+        return try loadLayout(name: name).displayMatch()
+    }
+
+    // MARK: - 取り消し
+
+    /// 復元直前の配置を取り消し用に保存（失敗しても復元は続行する）
+    private func saveUndoSnapshot() {
+        do {
+            let url = FileHelper.undoSnapshotURL()
+            try FileHelper.ensureParentDirectory(of: url)
+            try LayoutCrypto.encode(captureSnapshot(), encrypt: Config.load().encryptLayouts).write(to: url, options: [.atomic])
+        } catch {
+            Log.warning("[restore] 取り消し用スナップショットの保存に失敗: \(error.localizedDescription)")
+        }
+    }
+
+    /// 取り消し可能な復元があるか
+    func canUndoLastRestore() -> Bool {
+        return FileManager.default.fileExists(atPath: FileHelper.undoSnapshotURL().path)
+    }
+
+    /// 直前の復元を取り消し、復元前の配置に戻す
+    /// - アプリの起動は行わない（復元後に終了したアプリのウィンドウは戻さない）
+    /// - 取り消しは1回のみ（実行後はスナップショットを破棄する）
+    @discardableResult
+    func undoLastRestore() throws -> RestoreReport {
+        try preflight()
+        let url = FileHelper.undoSnapshotURL()
+        let snapshot = try LayoutCrypto.decode(Snapshot.self, from: Data(contentsOf: url))
+        var options = RestoreOptions(config: Config.load())
+        options.launchMissingApps = false
+        let report = restoreWindowList(snapshot.windows, options: options)
+        if report.permissionRevoked { throw PermissionDeniedError(missing: [.accessibility]) }
+        try? FileManager.default.removeItem(at: url)
+        return report
+    }
+
+    /// 設定の default_layout を復元（ショートカットやログイン時など、名前を指定しない復元用）
+    /// - default_layout が未設定、またはそのレイアウトが無い場合は FileHelperError.layoutNotFound
+    @discardableResult
+    func restoreDefault(progress: RestoreProgressHandler? = nil) throws -> RestoreReport {
+        guard let name = defaultLayoutName() else {
+            throw FileHelper.FileHelperError.layoutNotFound
+        }
+        return try restoreWindows(name: name, progress: progress)
+    }
+
+    /// 設定の default_layout（未設定、または保存されていない場合は nil）
+    func defaultLayoutName() -> String? {
+        guard let name = Config.load().defaultLayout?.trimmingCharacters(in: .whitespacesAndNewlines), !name.isEmpty else {
+            return nil
         }
-        let windows = try loadWindows(name: name)
-        for win in windows {
-            restoreSingleWindow(win)
-            // ウィンドウ間の僅かな間隔
-            usleep(200_000)
+        guard listLayouts().contains(name) else {
+            Log.warning("[restore] 既定のレイアウトが見つかりません: \(name)")
+            return nil
         }
+        return name
+    }
+
+    /// 指定アプリのウィンドウのみ復元（他のアプリのウィンドウには触れない）
+    /// - apps: bundle id またはアプリ名
+    @discardableResult
+    func restoreWindowsFiltered(name: String, apps: [String], progress: RestoreProgressHandler? = nil) throws -> RestoreReport {
+        var options = RestoreOptions(config: Config.load())
+        options.apps = apps
+        return try restoreWindows(name: name, options: options, progress: progress)
+    }
+
+    /// 指定ディスプレイに保存されていたウィンドウのみ復元（他のディスプレイのウィンドウには触れない）
+    @discardableResult
+    func restoreWindowsForDisplay(name: String, displayUUID uuid: String, progress: RestoreProgressHandler? = nil) throws -> RestoreReport {
+        var options = RestoreOptions(config: Config.load())
+        options.displayUUID = uuid
+        options.activateFocusApp = false
+        return try restoreWindows(name: name, options: options, progress: progress)
+    }
+
+    /// ウィンドウ配列を復元（options.launchMissingApps の場合は未起動のアプリを起動して待機）
+    /// - アプリ単位で並行に処理し（上限は Config.maxParallelApps）、同じアプリのウィンドウは背面→前面の順に処理する
+    /// - 保存時の重なり順を持つ場合は、全アプリの処理後に背面→前面の順で前面化する
+    /// - インストールされていないアプリは起動を試みず、結果の missingApps に集めて通知する
+    @discardableResult
+    private func restoreWindowList(_ windows: [WindowInfo],
+                                   options: RestoreOptions? = nil,
+                                   progress: RestoreProgressHandler? = nil) -> RestoreReport {
+        defer { invalidateCache() }
+        let config = Config.load()
+        let options = options ?? RestoreOptions(config: config)
+        // 制限時間（0 は無制限）
+        let deadline: Date? = config.restoreTimeoutMs > 0
+            ? Date().addingTimeInterval(Double(config.restoreTimeoutMs) / 1000) : nil
+        let isPastDeadline = { deadline.map { Date() >= $0 } ?? false }
+        var retry = RetryPolicy(config: config)
+        retry.deadline = deadline
+        var windows = Array(sortedByStackingOrder(windows).reversed())
+        if let apps = options.apps {
+            windows = windows.filter { $0.belongs(toAnyOf: apps) }
+        }
+        if let uuid = options.displayUUID {
+            windows = windows.filter { $0.displayUUID?.caseInsensitiveCompare(uuid) == .orderedSame }
+        }
+        // include モードでは対象リストに無いアプリを復元しない（保存後にリストを絞った場合）
+        if config.appFilterMode == .include {
+            windows = windows.filter { !config.isExcluded(bundleId: $0.bundleId, ownerName: $0.ownerName) }
+        }
+        var bundleIds = Set(windows.compactMap { $0.bundleId }.filter { !$0.isEmpty })
+        var notRunning = bundleIds.filter { AppLauncher.runningApplication(bundleId: $0) == nil }
+        var report = RestoreReport()
+        let launchedPids: [String: pid_t]
+        if options.launchMissingApps {
+            // 未インストールのアプリのウィンドウは対象外（保存時のPIDが別プロセスに再利用されている可能性もあるため）
+            let missing = notRunning.filter { !AppLauncher.isInstalled(bundleId: $0) }
+            if !missing.isEmpty {
+                Log.warning("[restore] インストールされていないアプリ: \(missing.sorted())")
+                report.missingApps = missing.sorted().map { bundleId in
+                    RestoreReport.MissingApp(bundleId: bundleId,
+                                             name: windows.first { $0.bundleId == bundleId }?.ownerName ?? "")
+                }
+                let isMissing = { (win: WindowInfo) in win.bundleId.map { missing.contains($0) } ?? false }
+                report.notFound += windows.filter(isMissing).count
+                windows.removeAll(where: isMissing)
+                bundleIds.subtract(missing)
+                notRunning.subtract(missing)
+            }
+            // 起動しない設定（app_overrides.*.skip_launch）のアプリは起動中の場合のみ対象
+            let skipped = notRunning.filter { config.skipsLaunch($0) }
+            if !skipped.isEmpty {
+                Log.info("[restore] 起動しない設定のため復元しないアプリ: \(skipped.sorted())")
+                windows.removeAll { win in win.bundleId.map { skipped.contains($0) } ?? false }
+                bundleIds.subtract(skipped)
+                notRunning.subtract(skipped)
+            }
+            let launchTimeout = min(AppLauncher.defaultLaunchTimeout,
+                                    deadline.map { max(0, $0.timeIntervalSinceNow) } ?? .greatestFiniteMagnitude)
+            launchedPids = AppLauncher.launchAll(bundleIds: bundleIds, timeout: launchTimeout)
+        } else {
+            // 起動中のアプリのみ対象（未起動のアプリのウィンドウ、終了済みプロセスの旧データは除外）
+            launchedPids = AppLauncher.launchAll(bundleIds: bundleIds.subtracting(notRunning))
+            windows = windows.filter { win in
+                if let bundleId = win.bundleId, !bundleId.isEmpty { return !notRunning.contains(bundleId) }
+                return NSRunningApplication(processIdentifier: pid_t(win.pid)) != nil
+            }
+            if !notRunning.isEmpty {
+                Log.info("[restore] 未起動のため復元しないアプリ: \(notRunning.sorted())")
+            }
+        }
+        let launchedAt = Date()
+        // bundle id で現在のPIDを解決（旧データは保存時のPIDを使用）
+        let pids = windows.map { win in win.bundleId.flatMap { launchedPids[$0] } ?? pid_t(win.pid) }
+        var indicesByPid: [pid_t: [Int]] = [:]
+        for index in windows.indices {
+            indicesByPid[pids[index], default: []].append(index)
+        }
+
+        let lock = NSLock()
+        var completed = 0
+        // 復元中の権限取り消し（AX 操作が失敗し始めた時点で確認し、以降のウィンドウは処理しない）
+        var revoked = false
+        let shouldStop = { () -> Bool in
+            lock.lock()
+            defer { lock.unlock() }
+            return revoked || isPastDeadline()
+        }
+        var restored: [(index: Int, pid: pid_t, element: AXUIElement)] = []
+        // ダイアログ表示中で操作できなかったウィンドウ（全アプリの処理後に再試行する）
+        var blocked: [(index: Int, pid: pid_t, target: AXUIElement?)] = []
+
+        let finish = { (index: Int, pid: pid_t, element: AXUIElement?, status: RestoreStatus) in
+            let win = windows[index]
+            lock.lock()
+            let current = completed
+            completed += 1
+            report.record(status)
+            if (status == .failed || status == .notFound) && !revoked && !PermissionChecker.checkAccessibility() {
+                revoked = true
+            }
+            // フルスクリーンのウィンドウは前面化すると Space が切り替わるため重なり順の再現から外す
+            if status == .restored, let element = element, win.isMinimized != true, win.isFullscreen != true {
+                restored.append((index, pid, element))
+            }
+            lock.unlock()
+            progress?(current, windows.count, win, status)
+        }
+
+        let queue = OperationQueue()
+        queue.name = "WindowManager.restore"
+        queue.maxConcurrentOperationCount = max(1, config.maxParallelApps)
+        for (pid, indices) in indicesByPid {
+            queue.addOperation { [self] in
+                // 今回起動したアプリはウィンドウを開き終えるまで待機（アプリ別の待機時間を優先）
+                if options.launchMissingApps, let bundleId = windows[indices[0]].bundleId, notRunning.contains(bundleId) {
+                    var readyAt = launchedAt.addingTimeInterval(Double(config.restoreDelayMs(for: bundleId)) / 1000)
+                    if let deadline = deadline { readyAt = min(readyAt, deadline) }
+                    let wait = readyAt.timeIntervalSinceNow
+                    if wait > 0 { usleep(useconds_t(min(wait * 1_000_000, Double(useconds_t.max)))) }
+                }
+                if shouldStop() { return }
+                // 起動直後のウィンドウ生成を待つため、割り当てはそのアプリの処理開始時に計算する
+                let targets = indices.filter {
+                    AccessibilityHelper.isRepositionable(role: windows[$0].axRole, subrole: windows[$0].axSubrole)
+                }
+                // ウィンドウがまだ現れていないアプリはリトライ方針に従って待機し直す
+                let matched = targets.isEmpty ? [:] : retry.run("ウィンドウ検出 pid=\(pid)") { () -> [Int: AXUIElement]? in
+                    let strategy = config.matchingStrategy(for: windows[targets[0]].bundleId)
+                    let result = WindowMatcher.assign(targets.map { windows[$0] }, pid: pid, strategy: strategy)
+                    return result.isEmpty ? nil : result
+                } ?? [:]
+                let assignment = Dictionary(uniqueKeysWithValues: matched.map { (targets[$0.key], $0.value) })
+
+                // AX で配置できなかったウィンドウは最後に AppleScript でまとめて配置する
+                var fallbacks: [(index: Int, move: AppleScriptMover.Move)] = []
+                for index in indices {
+                    if shouldStop() { break }
+                    let win = windows[index]
+                    var fallbackFrame: CGRect?
+                    let status = restoreSingleWindow(win, target: assignment[index], pid: pid,
+                                                     config: config, retry: retry, fallbackFrame: &fallbackFrame)
+                    if status == .failed, let frame = fallbackFrame {
+                        fallbacks.append((index, AppleScriptMover.Move(windowTitle: win.windowName, frame: frame)))
+                    } else if status == .blockedByDialog {
+                        lock.lock()
+                        blocked.append((index, pid, assignment[index]))
+                        lock.unlock()
+                    } else {
+                        finish(index, pid, assignment[index], status)
+                    }
+                    // ウィンドウ間の僅かな間隔
+                    usleep(200_000)
+                }
+                if !fallbacks.isEmpty {
+                    let outcome = AppleScriptMover.perform(pid: pid, moves: fallbacks.map { $0.move })
+                    if outcome.automationDenied {
+                        let win = windows[fallbacks[0].index]
+                        lock.lock()
+                        report.automationDeniedApps.append(win.bundleId ?? win.ownerName)
+                        lock.unlock()
+                    }
+                    for (fallback, succeeded) in zip(fallbacks, outcome.results) {
+                        finish(fallback.index, pid, assignment[fallback.index], succeeded ? .restored : .failed)
+                    }
+                }
+            }
+        }
+        queue.waitUntilAllOperationsAreFinished()
+
+        // 最終パス: ダイアログで止まっていたウィンドウを再試行（閉じられていなければ blockedByDialog として報告）
+        for item in blocked.sorted(by: { $0.index < $1.index }) {
+            if shouldStop() { break }
+            var fallbackFrame: CGRect?
+            var status = restoreSingleWindow(windows[item.index], target: item.target, pid: item.pid,
+                                             config: config, retry: retry, fallbackFrame: &fallbackFrame)
+            if status == .failed, let frame = fallbackFrame,
+               AppleScriptMover.setFrame(pid: item.pid, windowTitle: windows[item.index].windowName, frame: frame) {
+                status = .restored
+            }
+            finish(item.index, item.pid, item.target, status)
+        }
+        if revoked {
+            report.permissionRevoked = true
+            Log.error("[restore] 復元中にアクセシビリティ権限が取り消されたため打ち切りました: \(completed)/\(windows.count)")
+            handlePermissionRevoked()
+        } else if completed < windows.count && isPastDeadline() {
+            report.timedOut = true
+            Log.warning("[restore] 制限時間を超えたため打ち切りました: \(completed)/\(windows.count)")
+        }
+
+        if windows.contains(where: { $0.zIndex != nil }) {
+            raiseInOrder(restored.sorted { $0.index < $1.index }.map { ($0.pid, $0.element) })
+        }
+        // 保存時に非表示だったアプリを再び非表示にする（前面化でアクティブになるため最後に行う）
+        let hiddenPids = Set(windows.indices.filter { windows[$0].isHidden == true }.map { pids[$0] })
+        for pid in hiddenPids {
+            guard let app = NSRunningApplication(processIdentifier: pid), !app.isHidden else { continue }
+            if !app.hide() { Log.warning("[restore] アプリの非表示に失敗 pid=\(pid)") }
+        }
+
+        NotificationManager.warnMissingApps(report.missingApps)
+        if !report.automationDeniedApps.isEmpty {
+            NotificationManager.warn(title: "オートメーション権限が必要です",
+                                     body: "一部のウィンドウを配置できませんでした。システム設定 > プライバシーとセキュリティ > オートメーションで System Events を許可してください。")
+        }
+        Log.info("[restore] 完了: 復元\(report.restored) スキップ\(report.skipped) 未検出\(report.notFound) 失敗\(report.failed)")
+        return report
+    }
+
+    /// 背面→前面の順にウィンドウを前面化し、保存時の重なり順を再現する
+    /// - AXRaise だけではアプリ間の順序が変わらないため、各ウィンドウのアプリもアクティブにする
+    private func raiseInOrder(_ restored: [(pid: pid_t, element: AXUIElement)]) {
+        for (pid, element) in restored {
+            NSRunningApplication(processIdentifier: pid)?.activate(options: [])
+            let err = AXUIElementPerformAction(element, kAXRaiseAction as CFString)
+            if err != .success { Log.warning("[restore] 前面化失敗 pid=\(pid): \(err)") }
+            usleep(50_000)
+        }
+    }
+
+    // MARK: - テンプレート（ディスプレイに対する比率）
+
+    /// レイアウトを比率表現のテンプレートとして保存
+    func saveTemplate(name: String, fromLayout layoutName: String) throws {
+        var template = LayoutTemplate(layout: try loadLayout(name: layoutName))
+        template.name = name
+        let url = try FileHelper.templateFileURL(name: name)
+        try FileHelper.ensureParentDirectory(of: url)
+        try LayoutCrypto.encode(template, encrypt: Config.load().encryptLayouts).write(to: url, options: [.atomic])
+    }
+
+    func loadTemplate(name: String) throws -> LayoutTemplate {
+        return try LayoutCrypto.decode(LayoutTemplate.self, from: Data(contentsOf: FileHelper.templateFileURL(name: name)))
+    }
+
+    func listTemplates() -> [String] {
+        return FileHelper.listTemplateNames()
+    }
+
+    /// テンプレートを現在のディスプレイ構成に当てはめて復元
+    @discardableResult
+    func restoreTemplate(name: String, options: RestoreOptions? = nil, progress: RestoreProgressHandler? = nil) throws -> RestoreReport {
+        try preflight()
+        let layout = try loadTemplate(name: name).instantiate()
+        saveUndoSnapshot()
+        let report = restoreWindowList(layout.windows, options: options, progress: progress)
+        if report.permissionRevoked { throw PermissionDeniedError(missing: [.accessibility]) }
+        return report
     }
 
     // MARK: - マルチSpace: 追記保存/ラベルごと復元
 
     func saveWindowsAppend(name: String, label: String) throws {
-        try FileHelper.ensureDirectories()
-        let captured = fetchVisibleAppWindows().map { w in
-            WindowInfo(ownerName: w.ownerName,
-                       pid: w.pid,
-                       windowName: w.windowName,
-                       bounds: w.bounds,
-                       displayUUID: w.displayUUID,
-                       spaceNumber: w.spaceNumber,
-                       layoutLabel: label)
-        }
-        var existing: [WindowInfo] = []
-        if let list = try? loadWindows(name: name) { existing = list }
-        existing.append(contentsOf: captured)
-        let url = try FileHelper.layoutFileURL(name: name)
-        try FileHelper.saveJSON(existing, to: url)
+        try checkNotReserved(name)
+        let captured = fetchVisibleAppWindows().map { $0.withLabel(label) }
+        let displays = DisplayManager.currentDisplays()
+        try updateLayout(name: name) { layout in
+            layout.windows.append(contentsOf: captured)
+            layout.displays = displays
+        }
     }
 
     func layoutLabels(in name: String) -> [String] {
@@ -228,15 +1545,11 @@ final class WindowManager {
     }
 
     func restoreWindowsForLabel(name: String, label: String) throws {
-        guard hasAccessibilityPermission() else {
-            throw NSError(domain: "WindowManager", code: 1, userInfo: [NSLocalizedDescriptionKey: "アクセシビリティ権限が必要です"])
-        }
+        try preflight()
         let all = try loadWindows(name: name)
         let targets = all.filter { $0.layoutLabel == label }
-        for win in targets {
-            restoreSingleWindow(win)
-            usleep(200_000)
-        }
+        saveUndoSnapshot()
+        if restoreWindowList(targets).permissionRevoked { throw PermissionDeniedError(missing: [.accessibility]) }
     }
 
     func restoreWindowsInteractive(name: String, prompt: (String) -> Bool) throws {
@@ -270,45 +1583,88 @@ final class WindowManager {
     }
 
     func replaceWindowsForLabel(name: String, label: String, with newWindows: [WindowInfo]) throws {
-        var existing: [WindowInfo] = []
-        if let list = try? loadWindows(name: name) { existing = list }
-        let filtered = existing.filter { $0.layoutLabel != label }
-        let replaced = filtered + newWindows.map { w in
-            WindowInfo(ownerName: w.ownerName,
-                       pid: w.pid,
-                       windowName: w.windowName,
-                       bounds: w.bounds,
-                       displayUUID: w.displayUUID,
-                       spaceNumber: w.spaceNumber,
-                       layoutLabel: label)
-        }
-        let url = try FileHelper.layoutFileURL(name: name)
-        try FileHelper.saveJSON(replaced, to: url)
-    }
-
-    private func restoreSingleWindow(_ info: WindowInfo) {
-        let appRef = AXUIElementCreateApplication(pid_t(info.pid))
-
-        var windowsValue: CFTypeRef?
-        let err = AXUIElementCopyAttributeValue(appRef, kAXWindowsAttribute as CFString, &windowsValue)
-        guard err == .success, let axWindows = windowsValue as? [AXUIElement], let first = axWindows.first else {
-            print("[restore] ウィンドウ要素取得失敗 pid=\(info.pid) owner=\(info.ownerName)")
-            return
+        try updateLayout(name: name) { layout in
+            layout.windows = layout.windows.filter { $0.layoutLabel != label } + newWindows.map { $0.withLabel(label) }
         }
+    }
 
-        // 位置とサイズ設定
-        var pos = CGPoint(x: info.bounds.origin.x, y: info.bounds.origin.y)
-        var size = CGSize(width: info.bounds.size.width, height: info.bounds.size.height)
+    @discardableResult
+    private func restoreSingleWindow(_ info: WindowInfo,
+                                     target: AXUIElement?,
+                                     pid: pid_t,
+                                     config: Config,
+                                     retry: RetryPolicy,
+                                     fallbackFrame: inout CGRect?) -> RestoreStatus {
+        // シート/ダイアログ等は復元対象外
+        guard AccessibilityHelper.isRepositionable(role: info.axRole, subrole: info.axSubrole) else {
+            Log.debug("[restore] シート/ダイアログのためスキップ: \(info.ownerName) role=\(info.axRole ?? "-") subrole=\(info.axSubrole ?? "-")")
+            return .skipped
+        }
+        guard let target = target else {
+            Log.warning("[restore] ウィンドウ要素取得失敗 pid=\(pid) owner=\(info.ownerName)")
+            return .notFound
+        }
+        // モーダルダイアログ/シート表示中は位置設定が失敗し続けるため、リトライせず後回しにする
+        if AccessibilityHelper.isBlockedByDialog(target, pid: pid) {
+            Log.debug("[restore] ダイアログ表示中のため保留: \(info.ownerName)")
+            return .blockedByDialog
+        }
 
-        if let posValue = AXValueCreate(.cgPoint, &pos) {
-            let setPosErr = AXUIElementSetAttributeValue(first, kAXPositionAttribute as CFString, posValue)
-            if setPosErr != .success { print("[restore] 位置設定失敗: \(setPosErr)") }
+        // 保存時点で画面外にあったウィンドウは同じ位置へ戻しても見えないため移動しない
+        if info.isOffscreen == true {
+            Log.debug("[restore] 保存時に画面外だったためスキップ: \(info.ownerName)")
+            return .skipped
         }
-        if let sizeValue = AXValueCreate(.cgSize, &size) {
-            let setSizeErr = AXUIElementSetAttributeValue(first, kAXSizeAttribute as CFString, sizeValue)
-            if setSizeErr != .success { print("[restore] サイズ設定失敗: \(setSizeErr)") }
+
+        // 保存時のグローバル座標を、保存時ディスプレイ（取り外されている場合は最も近いディスプレイ）の現在の配置に合わせて変換
+        var frame = DisplayManager.mapFrame(info.bounds, fromDisplay: info.displayUUID, savedDisplayBounds: info.displayBounds)
+        // ディスプレイの取り外しや解像度変更で画面外になる場合は表示領域内へ寄せる
+        let clamped = DisplayManager.clampToVisibleArea(frame)
+        if clamped != frame {
+            Log.debug("[restore] 表示領域外のため位置を補正: \(info.ownerName) \(frame) -> \(clamped)")
+            frame = clamped
         }
-    }
-}
 
+        let isLiveFullscreen = AccessibilityHelper.bool(target, AccessibilityHelper.fullScreenAttribute) == true
+        // フルスクリーンで保存されたウィンドウはフレームではなく AXFullScreen で再現する（フルスクリーン中のフレームは設定不可）
+        if info.isFullscreen == true && config.restoreFullscreen {
+            if !isLiveFullscreen {
+                // 保存時のディスプレイへ移してからフルスクリーン化
+                AccessibilityHelper.setFrame(target, frame)
+                guard AccessibilityHelper.setBool(target, AccessibilityHelper.fullScreenAttribute, true) else {
+                    Log.warning("[restore] フルスクリーン化に失敗: \(info.ownerName)")
+                    return .failed
+                }
+                usleep(fullscreenTransitionDelay)
+            }
+            return .restored
+        }
+        // 現在フルスクリーンのウィンドウは解除してから配置
+        if isLiveFullscreen {
+            AccessibilityHelper.setBool(target, AccessibilityHelper.fullScreenAttribute, false)
+            usleep(fullscreenTransitionDelay)
+        }
+
+        // フルスクリーンを通常ウィンドウとして復元する場合は保存時ディスプレイの表示領域いっぱいに配置
+        if info.isFullscreen == true, let displayId = DisplayManager.displayID(for: frame) {
+            frame = DisplayManager.visibleFrame(for: displayId)
+        }
+
+        // サイズを自前で管理するアプリは現在のサイズのまま位置のみ合わせる
+        if config.restoresPositionOnly(info.bundleId), let size = AccessibilityHelper.size(of: target) {
+            frame.size = size
+        }
 
+        // 位置とサイズ設定（アプリ固有の配置方法が登録されていればそれを使用）
+        let strategy = RestoreStrategyRegistry.strategy(for: info.bundleId, config: config)
+        let applied = retry.run("配置 \(info.ownerName)") { () -> Bool? in
+            strategy.apply(frame: frame, to: target, info: info, pid: pid) ? true : nil
+        }
+        guard applied != nil else {
+            // AppleScript による一括配置の対象として呼び出し側へ返す
+            fallbackFrame = frame
+            return .failed
+        }
+        return .restored
+    }
+}
@@ -0,0 +1,153 @@
+import Cocoa
+import Foundation
+import ApplicationServices
+
+/// 保存済みウィンドウとライブウィンドウの対応付け方法（config.json の `app_overrides.*.matching`）
+enum WindowMatchingStrategy: String, Codable {
+    /// ウィンドウ番号・識別子・タイトル・位置の総合スコア（既定）
+    case score
+    /// タイトルが一致するウィンドウのみ（同じタイトルが複数ある場合はスコア順）
+    case title
+    /// 保存順とウィンドウの並び順で1対1に対応付ける（タイトルが頻繁に変わるアプリ向け）
+    case order
+}
+
+/// 復元時に保存済みウィンドウとライブウィンドウを対応付ける
+/// - アプリ（bundle id から解決した PID）単位で、全ペアをスコアリングして高い順に割り当てる
+/// - 同じアプリの複数ウィンドウが「先頭ウィンドウ」に集中しないよう、割り当て済みのウィンドウは再利用しない
+enum WindowMatcher {
+
+    // スコアの重み
+    private static let windowNumberWeight = 100.0
+    private static let identifierWeight = 50.0
+    private static let identifierMismatchPenalty = -20.0
+    private static let titleWeight = 30.0
+    private static let frameWeight = 20.0
+    private static let roleWeight = 5.0
+
+    // フレーム近接度がゼロになる距離（px）
+    private static let frameDistanceScale = 1000.0
+    // 同一フレームとみなす許容誤差（px）
+    private static let frameTolerance: CGFloat = 4.0
+
+    /// 同じプロセスに属する保存エントリ群をライブウィンドウへ割り当てる
+    /// 戻り値: saved のインデックス → 割り当てたウィンドウ（対応が無いエントリは含まない）
+    static func assign(_ saved: [WindowInfo], pid: pid_t, strategy: WindowMatchingStrategy = .score) -> [Int: AXUIElement] {
+        guard let candidates = AccessibilityHelper.windowSnapshots(pid: pid)?.filter({
+            AccessibilityHelper.isRepositionable(role: $0.role, subrole: $0.subrole)
+        }), !candidates.isEmpty else {
+            return [:]
+        }
+
+        if strategy == .order {
+            var result: [Int: AXUIElement] = [:]
+            for (i, candidate) in zip(saved.indices, candidates) {
+                result[i] = candidate.element
+            }
+            return result
+        }
+
+        var pairs: [(saved: Int, candidate: Int, score: Double)] = []
+        for (i, info) in saved.enumerated() {
+            let liveFrame = info.windowNumber.flatMap { currentBounds(ofWindowNumber: $0, pid: pid) }
+            for (j, candidate) in candidates.enumerated() {
+                if strategy == .title && !titlesEqual(info.windowName, candidate.title) { continue }
+                pairs.append((i, j, score(info, candidate, liveFrame: liveFrame)))
+            }
+        }
+        // 同点は保存順・ウィンドウ順を優先（従来の「先頭ウィンドウ」挙動と一致させる）
+        pairs.sort { a, b in
+            if a.score != b.score { return a.score > b.score }
+            if a.saved != b.saved { return a.saved < b.saved }
+            return a.candidate < b.candidate
+        }
+
+        var result: [Int: AXUIElement] = [:]
+        var usedCandidates = Set<Int>()
+        for pair in pairs where result[pair.saved] == nil && !usedCandidates.contains(pair.candidate) {
+            result[pair.saved] = candidates[pair.candidate].element
+            usedCandidates.insert(pair.candidate)
+        }
+        return result
+    }
+
+    /// 保存エントリとライブウィンドウの一致度
+    /// - liveFrame: 保存時の CGWindowNumber が同じプロセスで生存している場合、その現在フレーム
+    static func score(_ info: WindowInfo,
+                      _ candidate: AccessibilityHelper.WindowSnapshot,
+                      liveFrame: CGRect?) -> Double {
+        var score = 0.0
+        if let liveFrame = liveFrame, candidate.frame.isApproximatelyEqual(to: liveFrame, tolerance: frameTolerance) {
+            score += windowNumberWeight
+        }
+        if let identifier = info.axIdentifier, !identifier.isEmpty,
+           let candidateIdentifier = candidate.identifier, !candidateIdentifier.isEmpty {
+            score += identifier == candidateIdentifier ? identifierWeight : identifierMismatchPenalty
+        }
+        if let title = info.windowName, !title.isEmpty, let candidateTitle = candidate.title, !candidateTitle.isEmpty {
+            score += titleWeight * titleSimilarity(title, candidateTitle)
+        }
+        score += frameWeight * frameProximity(info.bounds, candidate.frame)
+        if let role = info.axRole, role == candidate.role, info.axSubrole == candidate.subrole {
+            score += roleWeight
+        }
+        return score
+    }
+
+    // MARK: - 類似度
+
+    /// タイトルの類似度（0〜1。編集距離を長い方の長さで正規化）
+    static func titleSimilarity(_ a: String, _ b: String) -> Double {
+        let x = Array(a.lowercased().trimmingCharacters(in: .whitespaces))
+        let y = Array(b.lowercased().trimmingCharacters(in: .whitespaces))
+        let longest = max(x.count, y.count)
+        guard longest > 0 else { return 1.0 }
+        return 1.0 - Double(editDistance(x, y)) / Double(longest)
+    }
+
+    /// フレームの近さ（0〜1。中心間距離とサイズ差の和で評価）
+    static func frameProximity(_ a: CGRect, _ b: CGRect) -> Double {
+        let dx = Double(a.midX - b.midX)
+        let dy = Double(a.midY - b.midY)
+        let sizeDiff = Double(abs(a.width - b.width) + abs(a.height - b.height))
+        let distance = (dx * dx + dy * dy).squareRoot() + sizeDiff
+        return max(0.0, 1.0 - distance / frameDistanceScale)
+    }
+
+    // 前後の空白と大文字小文字を無視して比較
+    private static func titlesEqual(_ a: String?, _ b: String?) -> Bool {
+        let x = (a ?? "").trimmingCharacters(in: .whitespaces)
+        let y = (b ?? "").trimmingCharacters(in: .whitespaces)
+        return x.caseInsensitiveCompare(y) == .orderedSame
+    }
+
+    // レーベンシュタイン距離
+    private static func editDistance(_ a: [Character], _ b: [Character]) -> Int {
+        if a.isEmpty { return b.count }
+        if b.isEmpty { return a.count }
+        var previous = Array(0...b.count)
+        var current = [Int](repeating: 0, count: b.count + 1)
+        for i in 1...a.count {
+            current[0] = i
+            for j in 1...b.count {
+                let cost = a[i - 1] == b[j - 1] ? 0 : 1
+                current[j] = min(previous[j] + 1, current[j - 1] + 1, previous[j - 1] + cost)
+            }
+            swap(&previous, &current)
+        }
+        return previous[b.count]
+    }
+
+    // MARK: - ウィンドウ番号
+
+    /// 指定ウィンドウ番号の現在のフレーム（所有PIDが一致する場合のみ）
+    private static func currentBounds(ofWindowNumber number: Int, pid: pid_t) -> CGRect? {
+        guard let list = CGWindowListCopyWindowInfo(.optionIncludingWindow, CGWindowID(number)) as? [[String: Any]],
+              let dict = list.first,
+              let ownerPid = dict[kCGWindowOwnerPID as String] as? Int, ownerPid == Int(pid),
+              let boundsDict = dict[kCGWindowBounds as String] as? NSDictionary,
+              let rect = CGRect(dictionaryRepresentation: boundsDict as CFDictionary)
+        else { return nil }
+        return rect
+    }
+}
@@ -0,0 +1,131 @@
+import Cocoa
+import Foundation
+import ApplicationServices
+
+/// ウィンドウの変化イベント
+enum WindowEvent {
+    case created(pid: pid_t, window: AXUIElement)
+    case moved(pid: pid_t, window: AXUIElement)
+    case resized(pid: pid_t, window: AXUIElement)
+    case minimized(pid: pid_t, window: AXUIElement)
+    case deminimized(pid: pid_t, window: AXUIElement)
+    case closed(pid: pid_t, window: AXUIElement)
+}
+
+/// AXObserver によるウィンドウイベントの監視
+/// - 通常アプリごとに AXObserver を登録し、アプリの起動/終了に追従する
+/// - イベントはメインRunLoop上でハンドラに通知される（アクセシビリティ権限が必要）
+final class WindowWatcher {
+    typealias Handler = (WindowEvent) -> Void
+
+    private let handler: Handler
+    private var observers: [pid_t: AXObserver] = [:]
+    private var workspaceTokens: [NSObjectProtocol] = []
+
+    // アプリ要素に登録する通知
+    private static let appNotifications: [String] = [
+        kAXWindowCreatedNotification,
+        kAXWindowMovedNotification,
+        kAXWindowResizedNotification,
+        kAXWindowMiniaturizedNotification,
+        kAXWindowDeminiaturizedNotification
+    ]
+
+    init(handler: @escaping Handler) {
+        self.handler = handler
+    }
+
+    deinit {
+        stop()
+    }
+
+    // MARK: - 開始/停止
+
+    func start() {
+        guard workspaceTokens.isEmpty else { return }
+        for app in NSWorkspace.shared.runningApplications where app.activationPolicy == .regular {
+            addObserver(for: app.processIdentifier)
+        }
+
+        let center = NSWorkspace.shared.notificationCenter
+        workspaceTokens.append(center.addObserver(forName: NSWorkspace.didLaunchApplicationNotification,
+                                                  object: nil, queue: .main) { [weak self] note in
+            guard let app = note.userInfo?[NSWorkspace.applicationUserInfoKey] as? NSRunningApplication,
+                  app.activationPolicy == .regular else { return }
+            self?.addObserver(for: app.processIdentifier)
+        })
+        workspaceTokens.append(center.addObserver(forName: NSWorkspace.didTerminateApplicationNotification,
+                                                  object: nil, queue: .main) { [weak self] note in
+            guard let app = note.userInfo?[NSWorkspace.applicationUserInfoKey] as? NSRunningApplication else { return }
+            self?.removeObserver(for: app.processIdentifier)
+        })
+        Log.debug("[watch] ウィンドウ監視を開始しました: \(observers.count)アプリ")
+    }
+
+    func stop() {
+        let center = NSWorkspace.shared.notificationCenter
+        workspaceTokens.forEach { center.removeObserver($0) }
+        workspaceTokens.removeAll()
+        for pid in Array(observers.keys) {
+            removeObserver(for: pid)
+        }
+    }
+
+    // MARK: - AXObserver 管理
+
+    private func addObserver(for pid: pid_t) {
+        guard observers[pid] == nil, pid != ProcessInfo.processInfo.processIdentifier else { return }
+        var created: AXObserver?
+        guard AXObserverCreate(pid, windowWatcherCallback, &created) == .success, let observer = created else {
+            return
+        }
+        let refcon = Unmanaged.passUnretained(self).toOpaque()
+        let appRef = AXUIElementCreateApplication(pid)
+        for notification in WindowWatcher.appNotifications {
+            AXObserverAddNotification(observer, appRef, notification as CFString, refcon)
+        }
+        // 既存ウィンドウの破棄通知（クローズ検知はウィンドウ要素ごとに登録が必要）
+        for window in AccessibilityHelper.windowSnapshots(pid: pid)?.map({ $0.element }) ?? [] {
+            AXObserverAddNotification(observer, window, kAXUIElementDestroyedNotification as CFString, refcon)
+        }
+        CFRunLoopAddSource(CFRunLoopGetMain(), AXObserverGetRunLoopSource(observer), .defaultMode)
+        observers[pid] = observer
+    }
+
+    private func removeObserver(for pid: pid_t) {
+        guard let observer = observers.removeValue(forKey: pid) else { return }
+        CFRunLoopRemoveSource(CFRunLoopGetMain(), AXObserverGetRunLoopSource(observer), .defaultMode)
+    }
+
+    // MARK: - 通知処理
+
+    fileprivate func handle(observer: AXObserver, element: AXUIElement, notification: String) {
+        guard let pid = observers.first(where: { $0.value == observer })?.key else { return }
+        switch notification {
+        case kAXWindowCreatedNotification:
+            // 新しいウィンドウのクローズを検知できるよう破棄通知を登録
+            let refcon = Unmanaged.passUnretained(self).toOpaque()
+            AXObserverAddNotification(observer, element, kAXUIElementDestroyedNotification as CFString, refcon)
+            handler(.created(pid: pid, window: element))
+        case kAXWindowMovedNotification:
+            handler(.moved(pid: pid, window: element))
+        case kAXWindowResizedNotification:
+            handler(.resized(pid: pid, window: element))
+        case kAXWindowMiniaturizedNotification:
+            handler(.minimized(pid: pid, window: element))
+        case kAXWindowDeminiaturizedNotification:
+            handler(.deminimized(pid: pid, window: element))
+        case kAXUIElementDestroyedNotification:
+            handler(.closed(pid: pid, window: element))
+        default:
+            break
+        }
+    }
+}
+
+// AXObserver の C コールバック（refcon に WindowWatcher を受け取る）
+private let windowWatcherCallback: AXObserverCallback = { observer, element, notification, refcon in
+    guard let refcon = refcon else { return }
+    let watcher = Unmanaged<WindowWatcher>.fromOpaque(refcon).takeUnretainedValue()
+    watcher.handle(observer: observer, element: element, notification: notification as String)
+}
@@ -0,0 +1,38 @@
+import XCTest
+@testable import mac_app
+
+final class AppMatcherTests: XCTestCase {
+
+    func testExactPatternMatchesBundleIdOrName() {
+        let matcher = AppMatcher(patterns: ["com.apple.finder", "Slack"])
+        XCTAssertTrue(matcher.matches(bundleId: "com.apple.finder", ownerName: "Finder"))
+        XCTAssertTrue(matcher.matches(bundleId: nil, ownerName: "slack"))
+        XCTAssertFalse(matcher.matches(bundleId: "com.apple.Finder", ownerName: "Finder"))
+    }
+
+    func testWildcardMatchesWholeBundleIdOrName() {
+        let matcher = AppMatcher(patterns: ["com.jetbrains.*", "*Helper?"])
+        XCTAssertTrue(matcher.matches(bundleId: "com.jetbrains.intellij", ownerName: "IntelliJ IDEA"))
+        XCTAssertTrue(matcher.matches(bundleId: nil, ownerName: "Chrome Helpers"))
+        XCTAssertFalse(matcher.matches(bundleId: "org.com.jetbrains.x", ownerName: "Chrome Helper"))
+    }
+
+    func testNameRegexOnlyMatchesName() {
+        let matcher = AppMatcher(patterns: ["name~\"Helper$\""])
+        XCTAssertTrue(matcher.matches(bundleId: "com.example.app", ownerName: "Example Helper"))
+        XCTAssertFalse(matcher.matches(bundleId: "com.example.Helper", ownerName: "Example"))
+    }
+
+    func testBundleRegexOnlyMatchesBundleId() {
+        let matcher = AppMatcher(patterns: ["bundle~^com\\.electron\\."])
+        XCTAssertTrue(matcher.matches(bundleId: "com.electron.app", ownerName: "App"))
+        XCTAssertFalse(matcher.matches(bundleId: nil, ownerName: "com.electron.app"))
+    }
+
+    func testInvalidAndEmptyPatterns() {
+        let matcher = AppMatcher(patterns: ["  ", "name~[", "Finder"])
+        XCTAssertEqual(matcher.invalidPatterns, ["name~["])
+        XCTAssertTrue(matcher.matches(bundleId: nil, ownerName: "Finder"))
+        XCTAssertFalse(matcher.matches(bundleId: nil, ownerName: ""))
+    }
+}
@@ -0,0 +1,51 @@
+import XCTest
+import CoreGraphics
+@testable import mac_app
+
+final class CoordinateSpaceTests: XCTestCase {
+
+    private let display = CGRect(x: 1920, y: -200, width: 2560, height: 1440)
+
+    func testCGToAppKitFlipsAroundMainDisplayHeight() {
+        let height = CGDisplayBounds(CGMainDisplayID()).height
+        let rect = CGRect(x: 10, y: 20, width: 300, height: 200)
+        XCTAssertEqual(DisplayManager.cgToAppKit(rect), CGRect(x: 10, y: height - 220, width: 300, height: 200))
+    }
+
+    func testAppKitRoundTrip() {
+        let rect = CGRect(x: -1280, y: 45, width: 1024, height: 768)
+        XCTAssertEqual(DisplayManager.appKitToCG(DisplayManager.cgToAppKit(rect)), rect)
+        XCTAssertEqual(DisplayManager.cgToAppKit(DisplayManager.appKitToCG(rect)), rect)
+    }
+
+    func testGlobalToDisplayLocalRoundTrip() {
+        let rect = CGRect(x: 2000, y: -100, width: 800, height: 600)
+        let local = DisplayManager.globalToDisplayLocal(rect, display: display)
+        XCTAssertEqual(local, CGRect(x: 80, y: 100, width: 800, height: 600))
+        XCTAssertEqual(DisplayManager.displayLocalToGlobal(local, display: display), rect)
+    }
+
+    func testNormalizedRoundTrip() throws {
+        let rect = CGRect(x: 2240, y: 160, width: 1280, height: 720)
+        let ratio = try XCTUnwrap(DisplayManager.normalized(rect, in: display))
+        XCTAssertEqual(ratio, CGRect(x: 0.125, y: 0.25, width: 0.5, height: 0.5))
+        XCTAssertEqual(DisplayManager.denormalized(ratio, in: display), rect)
+    }
+
+    func testNormalizedRejectsEmptyReference() {
+        let rect = CGRect(x: 0, y: 0, width: 100, height: 100)
+        XCTAssertNil(DisplayManager.normalized(rect, in: CGRect(x: 0, y: 0, width: 0, height: 900)))
+    }
+
+    func testDenormalizedRoundsToPixels() {
+        let reference = CGRect(x: 0, y: 0, width: 1000, height: 1000)
+        let third = CGRect(x: 1.0 / 3, y: 2.0 / 3, width: 1.0 / 3, height: 1.0 / 3)
+        XCTAssertEqual(DisplayManager.denormalized(third, in: reference), CGRect(x: 333, y: 667, width: 333, height: 333))
+    }
+
+    func testApproximatelyEqualUsesTolerancePerComponent() {
+        let rect = CGRect(x: 100, y: 100, width: 800, height: 600)
+        XCTAssertTrue(rect.isApproximatelyEqual(to: CGRect(x: 104, y: 96, width: 803, height: 600), tolerance: 4))
+        XCTAssertFalse(rect.isApproximatelyEqual(to: CGRect(x: 100, y: 100, width: 805, height: 600), tolerance: 4))
+    }
+}
@@ -0,0 +1,18 @@
+import CoreGraphics
+@testable import mac_app
+
+/// テスト用のウィンドウ情報
+func makeWindow(_ ownerName: String,
+                bundleId: String? = nil,
+                title: String? = nil,
+                bounds: CGRect = CGRect(x: 0, y: 0, width: 800, height: 600),
+                pid: Int = 100,
+                windowNumber: Int? = nil,
+                axIdentifier: String? = nil) -> WindowInfo {
+    var info = WindowInfo(ownerName: ownerName, pid: pid, windowName: title, bounds: bounds,
+                          displayUUID: nil, spaceNumber: nil, layoutLabel: nil)
+    info.bundleId = bundleId
+    info.windowNumber = windowNumber
+    info.axIdentifier = axIdentifier
+    return info
+}
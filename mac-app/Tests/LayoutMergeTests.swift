@@ -0,0 +1,77 @@
+import XCTest
+@testable import mac_app
+
+final class LayoutMergeTests: XCTestCase {
+
+    private let left = CGRect(x: 0, y: 0, width: 800, height: 600)
+    private let right = CGRect(x: 800, y: 0, width: 800, height: 600)
+
+    private var base: Layout {
+        var layout = Layout(name: "base", windows: [
+            makeWindow("Safari", bundleId: "com.apple.Safari", title: "Docs", bounds: left),
+            makeWindow("Mail", bundleId: "com.apple.mail", title: "Inbox", bounds: left),
+        ])
+        layout.tags = ["work"]
+        return layout
+    }
+
+    private var overlay: Layout {
+        var layout = Layout(name: "overlay", windows: [
+            makeWindow("Safari", bundleId: "com.apple.Safari", title: "Docs", bounds: right),
+            makeWindow("Notes", bundleId: "com.apple.Notes", title: "Todo", bounds: right),
+        ])
+        layout.focusApp = "com.apple.Notes"
+        return layout
+    }
+
+    func testPreferOverlayReplacesConflictsAndAppendsNewWindows() {
+        let merged = base.merged(with: overlay, strategy: .preferOverlay)
+        XCTAssertEqual(merged.windows.map(\.ownerName), ["Safari", "Mail", "Notes"])
+        XCTAssertEqual(merged.windows[0].bounds, right)
+        XCTAssertEqual(merged.name, "base")
+        XCTAssertEqual(merged.tags, ["work"])
+    }
+
+    func testPreferBaseKeepsConflictingWindows() {
+        let merged = base.merged(with: overlay, strategy: .preferBase)
+        XCTAssertEqual(merged.windows.map(\.ownerName), ["Safari", "Mail", "Notes"])
+        XCTAssertEqual(merged.windows[0].bounds, left)
+    }
+
+    func testReplaceAppsDropsAllBaseWindowsOfOverlayApps() {
+        var withExtra = base
+        withExtra.windows.append(makeWindow("Safari", bundleId: "com.apple.Safari", title: "News", bounds: left))
+        let merged = withExtra.merged(with: overlay, strategy: .replaceApps)
+        XCTAssertEqual(merged.windows.map(\.ownerName), ["Mail", "Safari", "Notes"])
+        XCTAssertEqual(merged.windows.filter { $0.ownerName == "Safari" }.map(\.windowName), ["Docs"])
+    }
+
+    func testDuplicateWindowsArePairedInOrder() {
+        let a = Layout(name: "a", windows: [
+            makeWindow("Terminal", title: "zsh", bounds: left),
+            makeWindow("Terminal", title: "zsh", bounds: left),
+        ])
+        let b = Layout(name: "b", windows: [
+            makeWindow("Terminal", title: "zsh", bounds: right),
+            makeWindow("Terminal", title: "zsh", bounds: right),
+            makeWindow("Terminal", title: "zsh", bounds: right),
+        ])
+        let merged = a.merged(with: b, strategy: .preferOverlay)
+        XCTAssertEqual(merged.windows.count, 3)
+        XCTAssertTrue(merged.windows.allSatisfy { $0.bounds == right })
+    }
+
+    func testIdentifierTakesPrecedenceOverTitle() {
+        let a = Layout(name: "a", windows: [makeWindow("Xcode", title: "Old", bounds: left, axIdentifier: "main")])
+        let b = Layout(name: "b", windows: [makeWindow("Xcode", title: "New", bounds: right, axIdentifier: "main")])
+        let merged = a.merged(with: b, strategy: .preferOverlay)
+        XCTAssertEqual(merged.windows.map(\.windowName), ["New"])
+    }
+
+    func testFocusAppIsInheritedOnlyWhenBaseHasNone() {
+        XCTAssertEqual(base.merged(with: overlay, strategy: .preferBase).focusApp, "com.apple.Notes")
+        var focused = base
+        focused.focusApp = "com.apple.mail"
+        XCTAssertEqual(focused.merged(with: overlay, strategy: .preferOverlay).focusApp, "com.apple.mail")
+    }
+}
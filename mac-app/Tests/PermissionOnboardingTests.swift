@@ -0,0 +1,92 @@
+import XCTest
+@testable import mac_app
+
+final class PermissionOnboardingTests: XCTestCase {
+
+    private func status(accessibility: Bool, automation: AutomationStatus) -> PermissionStatus {
+        let backends: [RestoreBackend] = automation == .denied ? [.accessibility] : [.accessibility, .appleScript]
+        return PermissionStatus(accessibility: accessibility, screenRecording: false, automation: automation,
+                                backends: backends, required: PermissionChecker.requiredPermissions(for: backends))
+    }
+
+    func testAllGrantedCompletesImmediately() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: true, automation: .granted)))
+        XCTAssertTrue(onboarding.isCompleted)
+        XCTAssertNil(onboarding.current)
+    }
+
+    func testMissingPermissionsAreVisitedInOrder() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: false, automation: .notDetermined)))
+        XCTAssertEqual(onboarding.step, .prompt)
+        XCTAssertEqual(onboarding.pending, [.accessibility, .automation])
+
+        onboarding.handle(.prompted)
+        XCTAssertEqual(onboarding.step, .verify)
+        onboarding.handle(.verified(granted: true))
+        XCTAssertEqual(onboarding.step, .prompt)
+        XCTAssertEqual(onboarding.current, .automation)
+
+        onboarding.handle(.prompted)
+        onboarding.handle(.verified(granted: true))
+        XCTAssertTrue(onboarding.isCompleted)
+    }
+
+    func testScreenRecordingIsNotRequested() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: false, automation: .granted)))
+        XCTAssertEqual(onboarding.pending, [.accessibility])
+    }
+
+    func testDeniedAutomationDisablesAppleScriptAndIsNotRequested() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: true, automation: .denied)))
+        XCTAssertTrue(onboarding.isCompleted)
+    }
+
+    func testUnlaunchableSystemEventsIsNotMissing() {
+        XCTAssertEqual(status(accessibility: true, automation: .targetNotRunning).missing, [])
+    }
+
+    func testFailedVerificationOpensSettings() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: false, automation: .granted)))
+        onboarding.handle(.prompted)
+        onboarding.handle(.verified(granted: false))
+        XCTAssertEqual(onboarding.step, .openSettings)
+        XCTAssertEqual(onboarding.attempts, 1)
+
+        onboarding.handle(.settingsOpened)
+        XCTAssertEqual(onboarding.step, .verify)
+    }
+
+    func testRepeatedFailuresSkipThePermission() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: false, automation: .notDetermined)))
+        onboarding.handle(.prompted)
+        for _ in 1..<PermissionOnboarding.maxAttempts {
+            onboarding.handle(.verified(granted: false))
+            onboarding.handle(.settingsOpened)
+        }
+        onboarding.handle(.verified(granted: false))
+        XCTAssertEqual(onboarding.skipped, [.accessibility])
+        XCTAssertEqual(onboarding.current, .automation)
+        XCTAssertEqual(onboarding.attempts, 0)
+    }
+
+    func testSkippedPermissionIsNotRequestedAgain() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.checked(status(accessibility: false, automation: .granted)))
+        onboarding.handle(.skipped)
+        XCTAssertTrue(onboarding.isCompleted)
+        XCTAssertEqual(onboarding.skipped, [.accessibility])
+    }
+
+    func testEventsForOtherStepsAreIgnored() {
+        var onboarding = PermissionOnboarding()
+        onboarding.handle(.prompted)
+        onboarding.handle(.verified(granted: true))
+        XCTAssertEqual(onboarding, PermissionOnboarding())
+    }
+}
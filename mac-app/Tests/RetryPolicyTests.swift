@@ -0,0 +1,43 @@
+import XCTest
+@testable import mac_app
+
+final class RetryPolicyTests: XCTestCase {
+
+    func testDelayGrowsByMultiplierUntilCap() {
+        let policy = RetryPolicy(maxAttempts: 5, initialDelayMs: 100, multiplier: 2.0, maxDelayMs: 1000)
+        XCTAssertEqual((1...5).map { policy.delayMs(beforeRetry: $0) }, [100, 200, 400, 800, 1000])
+    }
+
+    func testFirstRetryUsesInitialDelay() {
+        let policy = RetryPolicy(maxAttempts: 3, initialDelayMs: 250, multiplier: 1.5, maxDelayMs: 5000)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 0), 250)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 1), 250)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 2), 375)
+    }
+
+    func testMultiplierBelowOneKeepsDelayConstant() {
+        let policy = RetryPolicy(maxAttempts: 3, initialDelayMs: 100, multiplier: 0.5, maxDelayMs: 1000)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 3), 100)
+    }
+
+    func testMaxDelayBelowInitialDelayIsRaised() {
+        let policy = RetryPolicy(maxAttempts: 3, initialDelayMs: 500, multiplier: 2.0, maxDelayMs: 100)
+        XCTAssertEqual(policy.maxDelayMs, 500)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 2), 500)
+    }
+
+    func testLargeRetryCountDoesNotOverflow() {
+        let policy = RetryPolicy(maxAttempts: 1000, initialDelayMs: 100, multiplier: 2.0, maxDelayMs: UInt64.max)
+        XCTAssertEqual(policy.delayMs(beforeRetry: 1000), UInt64.max)
+    }
+
+    func testNegativeAttemptsAreClampedToZero() {
+        var calls = 0
+        let policy = RetryPolicy(maxAttempts: -1, initialDelayMs: 0, multiplier: 1.0, maxDelayMs: 0)
+        XCTAssertNil(policy.run("test") { () -> Int? in
+            calls += 1
+            return nil
+        })
+        XCTAssertEqual(calls, 1)
+    }
+}
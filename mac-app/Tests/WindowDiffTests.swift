@@ -0,0 +1,56 @@
+import XCTest
+@testable import mac_app
+
+final class WindowDiffTests: XCTestCase {
+
+    func testIdenticalSnapshotsHaveNoDiff() {
+        let windows = [makeWindow("Safari", windowNumber: 1), makeWindow("Mail", windowNumber: 2)]
+        XCTAssertTrue(WindowDiff.compute(previous: windows, current: windows).isEmpty)
+    }
+
+    func testSubPixelChangesAreNotMoves() {
+        let before = makeWindow("Safari", bounds: CGRect(x: 100, y: 100, width: 800, height: 600), windowNumber: 1)
+        let after = makeWindow("Safari", bounds: CGRect(x: 100.5, y: 99.5, width: 800, height: 600), windowNumber: 1)
+        XCTAssertTrue(WindowDiff.compute(previous: [before], current: [after]).isEmpty)
+    }
+
+    func testMovedWindowIsReportedWithBothFrames() {
+        let before = makeWindow("Safari", bounds: CGRect(x: 100, y: 100, width: 800, height: 600), windowNumber: 1)
+        let after = makeWindow("Safari", bounds: CGRect(x: 400, y: 100, width: 800, height: 600), windowNumber: 1)
+        let diff = WindowDiff.compute(previous: [before], current: [after])
+        XCTAssertTrue(diff.added.isEmpty)
+        XCTAssertTrue(diff.removed.isEmpty)
+        XCTAssertEqual(diff.moved.count, 1)
+        XCTAssertEqual(diff.moved.first?.previous.bounds.minX, 100)
+        XCTAssertEqual(diff.moved.first?.current.bounds.minX, 400)
+    }
+
+    func testAddedAndRemovedWindows() {
+        let kept = makeWindow("Safari", windowNumber: 1)
+        let closed = makeWindow("Mail", windowNumber: 2)
+        let opened = makeWindow("Notes", windowNumber: 3)
+        let diff = WindowDiff.compute(previous: [kept, closed], current: [kept, opened])
+        XCTAssertEqual(diff.added.map(\.ownerName), ["Notes"])
+        XCTAssertEqual(diff.removed.map(\.ownerName), ["Mail"])
+        XCTAssertTrue(diff.moved.isEmpty)
+    }
+
+    func testSameWindowNumberInAnotherProcessIsDifferentWindow() {
+        let before = makeWindow("Safari", pid: 100, windowNumber: 1)
+        let after = makeWindow("Safari", pid: 200, windowNumber: 1)
+        let diff = WindowDiff.compute(previous: [before], current: [after])
+        XCTAssertEqual(diff.added.count, 1)
+        XCTAssertEqual(diff.removed.count, 1)
+    }
+
+    func testWindowsWithoutNumberAreMatchedByAppAndTitle() {
+        let before = makeWindow("Mail", bundleId: "com.apple.mail", title: "Inbox",
+                                bounds: CGRect(x: 0, y: 0, width: 800, height: 600), pid: 100)
+        let after = makeWindow("Mail", bundleId: "com.apple.mail", title: "Inbox",
+                               bounds: CGRect(x: 0, y: 50, width: 800, height: 600), pid: 300)
+        let diff = WindowDiff.compute(previous: [before], current: [after])
+        XCTAssertTrue(diff.added.isEmpty)
+        XCTAssertTrue(diff.removed.isEmpty)
+        XCTAssertEqual(diff.moved.count, 1)
+    }
+}
@@ -0,0 +1,63 @@
+import XCTest
+import ApplicationServices
+@testable import mac_app
+
+final class WindowMatcherTests: XCTestCase {
+
+    private let frame = CGRect(x: 0, y: 0, width: 800, height: 600)
+
+    private func candidate(title: String?, identifier: String? = nil, frame: CGRect? = nil) -> AccessibilityHelper.WindowSnapshot {
+        return AccessibilityHelper.WindowSnapshot(element: AXUIElementCreateApplication(getpid()),
+                                                  title: title, identifier: identifier, role: nil, subrole: nil,
+                                                  frame: frame ?? self.frame, isMinimized: false, isFullscreen: false,
+                                                  isZoomed: false, tabTitles: nil, selectedTabIndex: nil)
+    }
+
+    // MARK: - titleSimilarity
+
+    func testTitleSimilarityIgnoresCaseAndSurroundingSpaces() {
+        XCTAssertEqual(WindowMatcher.titleSimilarity("  Inbox ", "inbox"), 1.0)
+    }
+
+    func testTitleSimilarityOfEmptyTitlesIsOne() {
+        XCTAssertEqual(WindowMatcher.titleSimilarity("", " "), 1.0)
+    }
+
+    func testTitleSimilarityIsNormalizedEditDistance() {
+        XCTAssertEqual(WindowMatcher.titleSimilarity("kitten", "sitting"), 1.0 - 3.0 / 7.0, accuracy: 1e-9)
+        XCTAssertEqual(WindowMatcher.titleSimilarity("abc", "xyz"), 0.0)
+    }
+
+    // MARK: - score
+
+    func testScoreCombinesTitleAndFrame() {
+        let info = makeWindow("Mail", title: "Inbox", bounds: frame)
+        XCTAssertEqual(WindowMatcher.score(info, candidate(title: "Inbox"), liveFrame: nil), 50.0, accuracy: 1e-9)
+    }
+
+    func testScorePrefersMatchingTitle() {
+        let info = makeWindow("Mail", title: "Inbox", bounds: frame)
+        let same = WindowMatcher.score(info, candidate(title: "Inbox"), liveFrame: nil)
+        let other = WindowMatcher.score(info, candidate(title: "Drafts"), liveFrame: nil)
+        XCTAssertGreaterThan(same, other)
+    }
+
+    func testScoreRewardsLiveWindowNumberFrame() {
+        let info = makeWindow("Mail", title: "Inbox", bounds: frame)
+        let live = CGRect(x: 2, y: 1, width: 800, height: 600)
+        let score = WindowMatcher.score(info, candidate(title: "Inbox", frame: live), liveFrame: frame)
+        XCTAssertGreaterThan(score, 100.0)
+    }
+
+    func testScorePenalizesIdentifierMismatch() {
+        let info = makeWindow("Xcode", title: "Project", bounds: frame, axIdentifier: "main")
+        let match = WindowMatcher.score(info, candidate(title: "Project", identifier: "main"), liveFrame: nil)
+        let mismatch = WindowMatcher.score(info, candidate(title: "Project", identifier: "inspector"), liveFrame: nil)
+        XCTAssertEqual(match - mismatch, 70.0, accuracy: 1e-9)
+    }
+
+    func testFrameProximityDropsToZeroWhenFarApart() {
+        XCTAssertEqual(WindowMatcher.frameProximity(frame, frame), 1.0)
+        XCTAssertEqual(WindowMatcher.frameProximity(frame, frame.offsetBy(dx: 3000, dy: 0)), 0.0)
+    }
+}
@@ -42,6 +42,10 @@ cat >"$APP_DIR/Contents/Info.plist" <<'PLIST'
   <true/>
   <key>NSPrincipalClass</key>
   <string>NSApplication</string>
+  <key>NSAppleEventsUsageDescription</key>
+  <string>アクセシビリティで配置できないウィンドウを System Events 経由で移動するために使用します。</string>
+  <key>NSFocusStatusUsageDescription</key>
+  <string>集中モード中に通知を控えるため、集中モードの状態を確認します。</string>
 </dict>
 </plist>
 PLIST